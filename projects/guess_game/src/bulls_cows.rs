@@ -0,0 +1,251 @@
+// Bulls and cows: the secret is a code of four different digits, and each
+// guess is told how many digits are right and in the right place (bulls) and
+// how many are right but in the wrong place (cows).
+
+use rand::seq::SliceRandom;
+use rand::Rng;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::game::State;
+
+pub const DIGITS: usize = 4;
+
+// One more than the solver ever needs.
+pub const MAX_ATTEMPTS: u32 = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Code([u8; DIGITS]);
+
+impl Code {
+    pub fn random(rng: &mut impl Rng) -> Code {
+        let mut digits: Vec<u8> = (0..10).collect();
+        digits.shuffle(rng);
+        Code([digits[0], digits[1], digits[2], digits[3]])
+    }
+
+    // Every code, in increasing order.
+    pub fn all() -> Vec<Code> {
+        (0..10_000)
+            .filter_map(|n: u32| format!("{n:04}").parse().ok())
+            .collect()
+    }
+
+    pub fn response(self, guess: Code) -> Response {
+        let bulls = (0..DIGITS).filter(|&i| self.0[i] == guess.0[i]).count();
+        let common = guess.0.iter().filter(|d| self.0.contains(d)).count();
+        Response {
+            bulls: bulls as u32,
+            cows: (common - bulls) as u32,
+        }
+    }
+}
+
+impl FromStr for Code {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("'{s}' isn't {DIGITS} different digits");
+
+        let digits: Vec<u8> = s
+            .chars()
+            .map(|c| c.to_digit(10).map(|d| d as u8))
+            .collect::<Option<_>>()
+            .ok_or_else(invalid)?;
+        let digits: [u8; DIGITS] = digits.try_into().map_err(|_| invalid())?;
+        if (1..DIGITS).any(|i| digits[..i].contains(&digits[i])) {
+            return Err(invalid());
+        }
+
+        Ok(Code(digits))
+    }
+}
+
+impl fmt::Display for Code {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for digit in self.0 {
+            write!(f, "{digit}")?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Response {
+    pub bulls: u32,
+    pub cows: u32,
+}
+
+impl Response {
+    pub fn is_correct(self) -> bool {
+        self.bulls == DIGITS as u32
+    }
+}
+
+impl fmt::Display for Response {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let plural = |n| if n == 1 { "" } else { "s" };
+        write!(
+            f,
+            "{} bull{}, {} cow{}",
+            self.bulls,
+            plural(self.bulls),
+            self.cows,
+            plural(self.cows)
+        )
+    }
+}
+
+// One round of bulls and cows, like `Game` is for guessing a number.
+#[derive(Debug, Clone)]
+pub struct BullsCows {
+    secret: Code,
+    attempts: u32,
+    state: State,
+}
+
+impl BullsCows {
+    pub fn new(rng: &mut impl Rng) -> BullsCows {
+        BullsCows::with_secret(Code::random(rng))
+    }
+
+    pub fn with_secret(secret: Code) -> BullsCows {
+        BullsCows {
+            secret,
+            attempts: 0,
+            state: State::Playing,
+        }
+    }
+
+    // Returns `None` once the game has finished, when guesses don't count.
+    pub fn guess(&mut self, guess: Code) -> Option<Response> {
+        if self.is_finished() {
+            return None;
+        }
+
+        self.attempts += 1;
+        let response = self.secret.response(guess);
+
+        if response.is_correct() {
+            self.state = State::Won;
+        } else if self.attempts == MAX_ATTEMPTS {
+            self.state = State::Lost;
+        }
+
+        Some(response)
+    }
+
+    pub fn secret(&self) -> Code {
+        self.secret
+    }
+
+    pub fn attempts(&self) -> u32 {
+        self.attempts
+    }
+
+    pub fn attempts_left(&self) -> u32 {
+        MAX_ATTEMPTS - self.attempts
+    }
+
+    pub fn state(&self) -> State {
+        self.state
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.state != State::Playing
+    }
+}
+
+// Guesses a code by only ever trying codes that fit every response so far.
+#[derive(Debug, Clone)]
+pub struct CodeSolver {
+    candidates: Vec<Code>,
+}
+
+impl CodeSolver {
+    pub fn new() -> CodeSolver {
+        CodeSolver {
+            candidates: Code::all(),
+        }
+    }
+
+    // `None` when no code fits the responses.
+    pub fn guess(&self) -> Option<Code> {
+        self.candidates.first().copied()
+    }
+
+    pub fn learn(&mut self, guess: Code, response: Response) {
+        self.candidates
+            .retain(|candidate| candidate.response(guess) == response);
+    }
+}
+
+impl Default for CodeSolver {
+    fn default() -> Self {
+        CodeSolver::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn code(s: &str) -> Code {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn codes_are_four_different_digits() {
+        assert_eq!(code("0123").to_string(), "0123");
+        assert_eq!(
+            "1123".parse::<Code>(),
+            Err("'1123' isn't 4 different digits".to_string())
+        );
+        assert!("123".parse::<Code>().is_err());
+        assert!("12345".parse::<Code>().is_err());
+        assert!("12a4".parse::<Code>().is_err());
+        assert_eq!(Code::all().len(), 10 * 9 * 8 * 7);
+    }
+
+    #[test]
+    fn responses_count_bulls_and_cows() {
+        let secret = code("1234");
+
+        assert_eq!(secret.response(code("1234")).to_string(), "4 bulls, 0 cows");
+        assert_eq!(secret.response(code("4321")).to_string(), "0 bulls, 4 cows");
+        assert_eq!(secret.response(code("1356")).to_string(), "1 bull, 1 cow");
+        assert_eq!(secret.response(code("5678")).to_string(), "0 bulls, 0 cows");
+    }
+
+    #[test]
+    fn game_ends_when_won_or_out_of_attempts() {
+        let mut won = BullsCows::with_secret(code("9876"));
+        assert!(!won.guess(code("6789")).unwrap().is_correct());
+        assert!(won.guess(code("9876")).unwrap().is_correct());
+        assert_eq!(won.state(), State::Won);
+        assert_eq!(won.guess(code("9876")), None);
+
+        let mut lost = BullsCows::with_secret(code("9876"));
+        for _ in 0..MAX_ATTEMPTS {
+            lost.guess(code("0123"));
+        }
+        assert_eq!(lost.state(), State::Lost);
+    }
+
+    #[test]
+    fn solver_finds_codes_within_the_attempts() {
+        // A spread of codes keeps the test quick; all of them fit within ten.
+        for secret in Code::all().into_iter().step_by(101) {
+            let mut game = BullsCows::with_secret(secret);
+            let mut solver = CodeSolver::new();
+
+            while !game.is_finished() {
+                let guess = solver.guess().unwrap();
+                let response = game.guess(guess).unwrap();
+                solver.learn(guess, response);
+            }
+
+            assert_eq!(game.state(), State::Won, "{secret} wasn't found");
+        }
+    }
+}
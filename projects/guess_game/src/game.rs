@@ -0,0 +1,526 @@
+use rand::Rng;
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Difficulty {
+    Easy,
+    Medium,
+    Hard,
+}
+
+impl Difficulty {
+    pub const ALL: [Difficulty; 3] = [Difficulty::Easy, Difficulty::Medium, Difficulty::Hard];
+
+    pub fn range(self) -> RangeInclusive<i64> {
+        match self {
+            Difficulty::Easy => 1..=10,
+            Difficulty::Medium => 1..=100,
+            Difficulty::Hard => 1..=1000,
+        }
+    }
+
+    // A few more than binary search needs, so careful players always win.
+    pub fn max_attempts(self) -> u32 {
+        match self {
+            Difficulty::Easy => 5,
+            Difficulty::Medium => 8,
+            Difficulty::Hard => 12,
+        }
+    }
+}
+
+impl FromStr for Difficulty {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "easy" => Ok(Difficulty::Easy),
+            "medium" => Ok(Difficulty::Medium),
+            "hard" => Ok(Difficulty::Hard),
+            _ => Err(format!(
+                "unknown difficulty '{s}' (expected easy, medium or hard)"
+            )),
+        }
+    }
+}
+
+impl fmt::Display for Difficulty {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Difficulty::Easy => "easy",
+            Difficulty::Medium => "medium",
+            Difficulty::Hard => "hard",
+        };
+        f.write_str(name)
+    }
+}
+
+// The most decimal places a precision game can have.
+pub const MAX_PRECISION: u32 = 9;
+
+// Why some text isn't a number a game can take.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BadNumber {
+    NotANumber,
+    // More decimal places than the game's precision.
+    TooPrecise,
+    // Too big to be in any game's range.
+    TooLarge,
+}
+
+// What a game is played over. Numbers have `precision` decimal places and are
+// stored as whole multiples of 10^-precision, so with a precision of 2 the
+// secret 3.14 is 314.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rules {
+    // The difficulty the rules started from.
+    pub difficulty: Difficulty,
+    pub low: i64,
+    pub high: i64,
+    pub precision: u32,
+    // Guesses at most this far from the secret win.
+    pub tolerance: i64,
+    pub max_attempts: u32,
+}
+
+impl Rules {
+    pub fn new(difficulty: Difficulty) -> Rules {
+        Rules {
+            difficulty,
+            low: *difficulty.range().start(),
+            high: *difficulty.range().end(),
+            precision: 0,
+            tolerance: 0,
+            max_attempts: difficulty.max_attempts(),
+        }
+    }
+
+    // The rules for `difficulty`, with its range, precision and tolerance
+    // replaced by any that are given.
+    pub fn build(
+        difficulty: Difficulty,
+        min: Option<&str>,
+        max: Option<&str>,
+        precision: Option<u32>,
+        tolerance: Option<&str>,
+    ) -> Result<Rules, String> {
+        let mut rules = Rules::new(difficulty);
+        if min.is_none() && max.is_none() && precision.is_none() && tolerance.is_none() {
+            return Ok(rules);
+        }
+
+        rules.precision = precision.unwrap_or(0);
+        if rules.precision > MAX_PRECISION {
+            return Err(format!(
+                "precision can be at most {MAX_PRECISION} decimal places"
+            ));
+        }
+
+        let scale = 10_i64.pow(rules.precision);
+        let parse = |flag: &str, value: &str| {
+            rules.parse(value).map_err(|e| match e {
+                BadNumber::TooPrecise => format!(
+                    "'{value}' for '{flag}' has more than {} decimal places",
+                    rules.precision
+                ),
+                _ => format!("invalid number '{value}' for '{flag}'"),
+            })
+        };
+        let low = match min {
+            Some(min) => parse("--min", min)?,
+            None => rules.low * scale,
+        };
+        let high = match max {
+            Some(max) => parse("--max", max)?,
+            None => rules.high * scale,
+        };
+        let tolerance = match tolerance {
+            Some(tolerance) => parse("--tolerance", tolerance)?,
+            None => 0,
+        };
+
+        if low >= high {
+            return Err(format!(
+                "'--min' ({}) must be less than '--max' ({})",
+                rules.format(low),
+                rules.format(high)
+            ));
+        }
+        if tolerance < 0 {
+            return Err("'--tolerance' can't be negative".to_string());
+        }
+
+        // Enough for binary search to narrow the range down to one winning
+        // window, plus a couple to spare.
+        let values = (i128::from(high) - i128::from(low) + 1) as u128;
+        let windows = values.div_ceil(2 * tolerance as u128 + 1);
+        rules.max_attempts = (u128::BITS - (windows - 1).leading_zeros()) + 2;
+
+        rules.low = low;
+        rules.high = high;
+        rules.tolerance = tolerance;
+        Ok(rules)
+    }
+
+    pub fn range(&self) -> RangeInclusive<i64> {
+        self.low..=self.high
+    }
+
+    // Whether these are a difficulty's rules, unchanged.
+    pub fn is_preset(&self) -> bool {
+        *self == Rules::new(self.difficulty)
+    }
+
+    // Reads a number such as `-12` or `3.14`, with at most `precision`
+    // decimal places.
+    pub fn parse(&self, text: &str) -> Result<i64, BadNumber> {
+        let text = text.trim();
+        let (negative, digits) = match text.strip_prefix('-') {
+            Some(digits) => (true, digits),
+            None => (false, text.strip_prefix('+').unwrap_or(text)),
+        };
+        let (whole, fraction) = digits.split_once('.').unwrap_or((digits, ""));
+
+        let is_digits = |s: &str| s.bytes().all(|b| b.is_ascii_digit());
+        if whole.is_empty() || !is_digits(whole) || !is_digits(fraction) || digits.ends_with('.') {
+            return Err(BadNumber::NotANumber);
+        }
+        if fraction.len() > self.precision as usize {
+            return Err(BadNumber::TooPrecise);
+        }
+
+        let mut value: i128 = 0;
+        for digit in whole.bytes().chain(fraction.bytes()) {
+            value = value * 10 + i128::from(digit - b'0');
+            if value > i128::from(i64::MAX) {
+                return Err(BadNumber::TooLarge);
+            }
+        }
+        let padding = self.precision - fraction.len() as u32;
+        let value = value * 10_i128.pow(padding);
+        let value = if negative { -value } else { value };
+
+        i64::try_from(value).map_err(|_| BadNumber::TooLarge)
+    }
+
+    // Writes `value` with exactly `precision` decimal places.
+    pub fn format(&self, value: i64) -> String {
+        if self.precision == 0 {
+            return value.to_string();
+        }
+        let scale = 10_u64.pow(self.precision);
+        let sign = if value < 0 { "-" } else { "" };
+        let magnitude = value.unsigned_abs();
+        format!(
+            "{sign}{}.{:0width$}",
+            magnitude / scale,
+            magnitude % scale,
+            width = self.precision as usize
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Feedback {
+    TooSmall,
+    TooBig,
+    Correct,
+    // Outside the game's range, so the guess didn't count.
+    OutOfRange,
+    // The game had already finished, so the guess didn't count.
+    GameOver,
+}
+
+impl Feedback {
+    // How the guess compared with the secret, for guesses that counted.
+    pub fn ordering(self) -> Option<Ordering> {
+        match self {
+            Feedback::TooSmall => Some(Ordering::Less),
+            Feedback::TooBig => Some(Ordering::Greater),
+            Feedback::Correct => Some(Ordering::Equal),
+            Feedback::OutOfRange | Feedback::GameOver => None,
+        }
+    }
+}
+
+// How a guess compares with the one before it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Hint {
+    Warmer,
+    Colder,
+    Same,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum State {
+    Playing,
+    Won,
+    // Every attempt was used up without finding the secret.
+    Lost,
+}
+
+// One round of guessing a secret number, with no notion of where the guesses
+// come from or how the feedback is shown.
+#[derive(Debug, Clone)]
+pub struct Game {
+    rules: Rules,
+    secret: i64,
+    attempts: u32,
+    state: State,
+    // How far the last two counted guesses were from the secret.
+    distances: (Option<u64>, Option<u64>),
+}
+
+impl Game {
+    pub fn new(rules: Rules, rng: &mut impl Rng) -> Game {
+        let secret = rng.gen_range(rules.range());
+        Game::with_secret(rules, secret)
+    }
+
+    pub fn with_secret(rules: Rules, secret: i64) -> Game {
+        Game {
+            rules,
+            secret,
+            attempts: 0,
+            state: State::Playing,
+            distances: (None, None),
+        }
+    }
+
+    pub fn guess(&mut self, guess: i64) -> Feedback {
+        if self.is_finished() {
+            return Feedback::GameOver;
+        }
+        if !self.range().contains(&guess) {
+            return Feedback::OutOfRange;
+        }
+
+        self.attempts += 1;
+        let distance = guess.abs_diff(self.secret);
+        self.distances = (self.distances.1, Some(distance));
+
+        let feedback = if distance <= self.rules.tolerance as u64 {
+            Feedback::Correct
+        } else {
+            match guess.cmp(&self.secret) {
+                Ordering::Less => Feedback::TooSmall,
+                Ordering::Greater => Feedback::TooBig,
+                Ordering::Equal => Feedback::Correct,
+            }
+        };
+
+        if feedback == Feedback::Correct {
+            self.state = State::Won;
+        } else if self.attempts_left() == 0 {
+            self.state = State::Lost;
+        }
+
+        feedback
+    }
+
+    // Whether the last counted guess was closer to the secret than the one
+    // before it. There's no hint until two guesses have been made.
+    pub fn hint(&self) -> Option<Hint> {
+        let (Some(before), Some(last)) = self.distances else {
+            return None;
+        };
+        Some(match last.cmp(&before) {
+            Ordering::Less => Hint::Warmer,
+            Ordering::Greater => Hint::Colder,
+            Ordering::Equal => Hint::Same,
+        })
+    }
+
+    pub fn rules(&self) -> &Rules {
+        &self.rules
+    }
+
+    pub fn difficulty(&self) -> Difficulty {
+        self.rules.difficulty
+    }
+
+    pub fn range(&self) -> RangeInclusive<i64> {
+        self.rules.range()
+    }
+
+    pub fn secret(&self) -> i64 {
+        self.secret
+    }
+
+    pub fn attempts(&self) -> u32 {
+        self.attempts
+    }
+
+    pub fn attempts_left(&self) -> u32 {
+        self.rules.max_attempts - self.attempts
+    }
+
+    pub fn state(&self) -> State {
+        self.state
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.state != State::Playing
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn guesses_get_feedback_until_won() {
+        let mut game = Game::with_secret(Rules::new(Difficulty::Medium), 42);
+
+        assert_eq!(game.guess(50), Feedback::TooBig);
+        assert_eq!(game.guess(25), Feedback::TooSmall);
+        assert_eq!(game.state(), State::Playing);
+        assert_eq!(game.guess(42), Feedback::Correct);
+
+        assert_eq!(game.state(), State::Won);
+        assert_eq!(game.attempts(), 3);
+        assert_eq!(game.attempts_left(), 5);
+    }
+
+    #[test]
+    fn running_out_of_attempts_loses() {
+        let mut game = Game::with_secret(Rules::new(Difficulty::Easy), 7);
+
+        for _ in 0..5 {
+            assert_eq!(game.guess(1), Feedback::TooSmall);
+        }
+
+        assert_eq!(game.state(), State::Lost);
+        assert!(game.is_finished());
+    }
+
+    #[test]
+    fn guesses_out_of_range_do_not_count() {
+        let mut game = Game::with_secret(Rules::new(Difficulty::Easy), 7);
+
+        assert_eq!(game.guess(0), Feedback::OutOfRange);
+        assert_eq!(game.guess(11), Feedback::OutOfRange);
+        assert_eq!(game.attempts(), 0);
+    }
+
+    #[test]
+    fn hints_compare_consecutive_guesses() {
+        let mut game = Game::with_secret(Rules::new(Difficulty::Medium), 42);
+
+        game.guess(10);
+        assert_eq!(game.hint(), None);
+        game.guess(60);
+        assert_eq!(game.hint(), Some(Hint::Warmer));
+        game.guess(90);
+        assert_eq!(game.hint(), Some(Hint::Colder));
+        game.guess(200);
+        assert_eq!(game.hint(), Some(Hint::Colder));
+        game.guess(90);
+        assert_eq!(game.hint(), Some(Hint::Same));
+    }
+
+    #[test]
+    fn guesses_after_the_end_do_not_count() {
+        let mut game = Game::with_secret(Rules::new(Difficulty::Easy), 7);
+        game.guess(7);
+
+        assert_eq!(game.guess(7), Feedback::GameOver);
+        assert_eq!(game.attempts(), 1);
+        assert_eq!(game.state(), State::Won);
+    }
+
+    fn precision(min: &str, max: &str, precision: u32, tolerance: &str) -> Rules {
+        Rules::build(
+            Difficulty::Medium,
+            Some(min),
+            Some(max),
+            Some(precision),
+            Some(tolerance),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn presets_are_unchanged_without_options() {
+        let rules = Rules::build(Difficulty::Hard, None, None, None, None).unwrap();
+
+        assert_eq!(rules, Rules::new(Difficulty::Hard));
+        assert!(rules.is_preset());
+        assert_eq!(rules.range(), 1..=1000);
+    }
+
+    #[test]
+    fn custom_ranges_can_be_negative() {
+        let rules = Rules::build(Difficulty::Easy, Some("-500"), Some("500"), None, None).unwrap();
+
+        assert_eq!(rules.range(), -500..=500);
+        assert!(!rules.is_preset());
+        // 1001 numbers take binary search 10 guesses.
+        assert_eq!(rules.max_attempts, 12);
+
+        let mut game = Game::with_secret(rules, -123);
+        assert_eq!(game.guess(-500), Feedback::TooSmall);
+        assert_eq!(game.guess(0), Feedback::TooBig);
+        assert_eq!(game.guess(-501), Feedback::OutOfRange);
+        assert_eq!(game.guess(-123), Feedback::Correct);
+    }
+
+    #[test]
+    fn min_must_be_less_than_max() {
+        let error = |min, max| Rules::build(Difficulty::Easy, min, max, None, None).unwrap_err();
+
+        assert_eq!(
+            error(Some("10"), Some("10")),
+            "'--min' (10) must be less than '--max' (10)"
+        );
+        assert_eq!(
+            error(Some("50"), None),
+            "'--min' (50) must be less than '--max' (10)"
+        );
+        assert_eq!(error(Some("ten"), None), "invalid number 'ten' for '--min'");
+    }
+
+    #[test]
+    fn precision_games_win_within_the_tolerance() {
+        let rules = precision("0", "1", 3, "0.01");
+        assert_eq!(rules.range(), 0..=1000);
+        assert_eq!(rules.tolerance, 10);
+
+        let mut game = Game::with_secret(rules.clone(), 314);
+        assert_eq!(game.guess(rules.parse("0.3").unwrap()), Feedback::TooSmall);
+        assert_eq!(game.guess(rules.parse("0.325").unwrap()), Feedback::TooBig);
+        assert_eq!(game.guess(rules.parse("0.324").unwrap()), Feedback::Correct);
+    }
+
+    #[test]
+    fn numbers_are_read_and_written_with_their_precision() {
+        let rules = precision("-5", "5", 2, "0");
+
+        assert_eq!(rules.parse("3.14"), Ok(314));
+        assert_eq!(rules.parse("-0.5"), Ok(-50));
+        assert_eq!(rules.parse("+2"), Ok(200));
+        assert_eq!(rules.parse("3.141"), Err(BadNumber::TooPrecise));
+        assert_eq!(rules.parse("3."), Err(BadNumber::NotANumber));
+        assert_eq!(rules.parse(".5"), Err(BadNumber::NotANumber));
+        assert_eq!(rules.parse("1e3"), Err(BadNumber::NotANumber));
+        assert_eq!(
+            rules.parse("99999999999999999999"),
+            Err(BadNumber::TooLarge)
+        );
+
+        assert_eq!(rules.format(314), "3.14");
+        assert_eq!(rules.format(-50), "-0.50");
+        assert_eq!(rules.format(7), "0.07");
+        assert_eq!(Rules::new(Difficulty::Easy).format(-7), "-7");
+    }
+
+    #[test]
+    fn precision_is_limited() {
+        let error = Rules::build(Difficulty::Easy, None, None, Some(10), None).unwrap_err();
+
+        assert_eq!(error, "precision can be at most 9 decimal places");
+    }
+}
@@ -0,0 +1,926 @@
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::cmp::Ordering;
+use std::env;
+use std::error::Error;
+use std::io::{self, BufRead, Write};
+use std::path::Path;
+use std::str::FromStr;
+use std::time::Instant;
+
+pub mod bulls_cows;
+pub mod game;
+pub mod scores;
+pub mod server;
+pub mod solver;
+
+pub use bulls_cows::{BullsCows, Code, CodeSolver, Response};
+pub use game::{BadNumber, Difficulty, Feedback, Game, Hint, Rules, State};
+use scores::Score;
+pub use server::Server;
+pub use solver::{Solver, Strategy};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Variant {
+    // Guess a number, told whether each guess is too small or too big.
+    Number,
+    // Guess a code of four different digits, told its bulls and cows.
+    BullsCows,
+}
+
+impl FromStr for Variant {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "number" => Ok(Variant::Number),
+            "bulls-cows" => Ok(Variant::BullsCows),
+            _ => Err(format!(
+                "unknown variant '{s}' (expected number or bulls-cows)"
+            )),
+        }
+    }
+}
+
+pub enum Mode {
+    Play,
+    // Print the high-score table.
+    Scores,
+    // Let a solver play one game.
+    Auto,
+    // Let each solver play this many games, and compare them.
+    Simulate(u32),
+    // The player thinks of a number and the computer guesses it.
+    Reverse,
+    // Host a game for players connecting to this address.
+    Serve(String),
+}
+
+pub struct Config {
+    pub mode: Mode,
+    pub variant: Variant,
+    // Recorded with each win in the high-score table.
+    pub name: String,
+    // The difficulty, and any range, precision and tolerance replacing its own.
+    pub rules: Rules,
+    // Shows the secret number, for trying the game out.
+    pub debug: bool,
+    // Picks the same secret number every time, for reproducible games.
+    pub seed: Option<u64>,
+    // The solver used by `--auto`, `--reverse` and `--simulate`; `--simulate` compares
+    // every strategy when it's not given.
+    pub strategy: Option<Strategy>,
+}
+
+impl Config {
+    pub fn build(mut args: impl Iterator<Item = String>) -> Result<Config, String> {
+        args.next();
+
+        let mut mode = Mode::Play;
+        let mut variant = Variant::Number;
+        let mut name = None;
+        let mut difficulty = Difficulty::Medium;
+        let mut debug = false;
+        let mut seed = None;
+
+        let mut strategy = None;
+        let mut min = None;
+        let mut max = None;
+        let mut precision = None;
+        let mut tolerance = None;
+
+        while let Some(arg) = args.next() {
+            // Values can be given as `--flag=VALUE` or `--flag VALUE`.
+            let (flag, mut inline) = match arg.split_once('=') {
+                Some((flag, value)) => (flag, Some(value.to_string())),
+                None => (arg.as_str(), None),
+            };
+            let mut value = || {
+                inline
+                    .take()
+                    .or_else(|| args.next())
+                    .ok_or(format!("'{flag}' needs a value"))
+            };
+
+            match flag {
+                "--debug" => debug = true,
+                "--scores" => mode = Mode::Scores,
+                "--auto" => mode = Mode::Auto,
+                "--reverse" => mode = Mode::Reverse,
+                "--serve" => mode = Mode::Serve(value()?),
+                "--simulate" => {
+                    let value = value()?;
+                    let games = value
+                        .parse()
+                        .map_err(|_| format!("invalid number of games '{value}'"))?;
+                    mode = Mode::Simulate(games);
+                }
+                "--strategy" => strategy = Some(value()?.parse()?),
+                "--variant" => variant = value()?.parse()?,
+                "--name" => name = Some(value()?),
+                "--difficulty" => difficulty = value()?.parse()?,
+                "--seed" => seed = Some(parse_seed(&value()?)?),
+                "--min" => min = Some(value()?),
+                "--max" => max = Some(value()?),
+                "--tolerance" => tolerance = Some(value()?),
+                "--precision" => {
+                    let value = value()?;
+                    let digits = value
+                        .parse()
+                        .map_err(|_| format!("invalid precision '{value}'"))?;
+                    precision = Some(digits);
+                }
+                _ => return Err(format!("unknown argument '{arg}'")),
+            }
+        }
+
+        let name = name
+            .or_else(|| env::var("USER").ok())
+            .unwrap_or_else(|| "anonymous".to_string());
+
+        let rules = Rules::build(
+            difficulty,
+            min.as_deref(),
+            max.as_deref(),
+            precision,
+            tolerance.as_deref(),
+        )?;
+
+        Ok(Config {
+            mode,
+            variant,
+            name,
+            rules,
+            debug,
+            seed,
+            strategy,
+        })
+    }
+
+    pub fn rng(&self) -> StdRng {
+        match self.seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        }
+    }
+}
+
+fn parse_seed(value: &str) -> Result<u64, String> {
+    value
+        .parse()
+        .map_err(|_| format!("invalid seed '{value}' (expected a whole number)"))
+}
+
+pub fn run(config: Config) -> Result<(), Box<dyn Error>> {
+    let path = scores::path();
+
+    if let Mode::Scores = config.mode {
+        let path =
+            path.ok_or("can't find the scores file: neither XDG_DATA_HOME nor HOME is set")?;
+        let scores = scores::load(&path).map_err(|e| format!("{}: {e}", path.display()))?;
+        warn_skipped(&path, scores.skipped);
+        print!("{}", scores::leaderboard(&scores.scores));
+        return Ok(());
+    }
+
+    if config.variant == Variant::BullsCows {
+        let mut rng = config.rng();
+        let mut game = BullsCows::new(&mut rng);
+        match config.mode {
+            Mode::Play => {
+                play_bulls_cows(&mut game, config.debug, io::stdin().lock(), io::stdout())?
+            }
+            Mode::Auto => auto_bulls_cows(&mut game, io::stdout())?,
+            _ => {
+                return Err(
+                    "'--variant bulls-cows' can only be played, or solved with '--auto'".into(),
+                )
+            }
+        };
+        return Ok(());
+    }
+
+    if let Mode::Simulate(games) = config.mode {
+        // Simulations are reproducible unless asked otherwise.
+        let seed = config.seed.unwrap_or(0);
+        let rules = &config.rules;
+        println!(
+            "Simulated {games} games between {} and {}:",
+            rules.format(rules.low),
+            rules.format(rules.high)
+        );
+        for strategy in config.strategy.map_or(Strategy::ALL.to_vec(), |s| vec![s]) {
+            println!("{}", solver::simulate(strategy, rules, games, seed));
+        }
+        return Ok(());
+    }
+
+    if let Mode::Auto = config.mode {
+        let mut rng = config.rng();
+        let mut game = Game::new(config.rules.clone(), &mut rng);
+        let strategy = config.strategy.unwrap_or(Strategy::BinarySearch);
+        auto(&mut game, strategy, &mut rng, io::stdout())?;
+        return Ok(());
+    }
+
+    if let Mode::Reverse = config.mode {
+        let strategy = config.strategy.unwrap_or(Strategy::BinarySearch);
+        let mut rng = config.rng();
+        reverse(
+            &config.rules,
+            strategy,
+            &mut rng,
+            io::stdin().lock(),
+            io::stdout(),
+        )?;
+        return Ok(());
+    }
+
+    if let Mode::Serve(addr) = &config.mode {
+        let server = Server::bind(addr.as_str(), config.rules.clone(), &mut config.rng())
+            .map_err(|e| format!("{addr}: {e}"))?;
+        let rules = server.rules();
+        println!(
+            "Serving a game between {} and {} on {}",
+            rules.format(rules.low),
+            rules.format(rules.high),
+            server.local_addr()?
+        );
+        if config.debug {
+            println!("the secret number is: {}", rules.format(server.secret()));
+        }
+        let winner = server.run()?;
+        println!("{winner} won!");
+        return Ok(());
+    }
+
+    let mut game = Game::new(config.rules.clone(), &mut config.rng());
+    let start = Instant::now();
+    let state = play(&mut game, config.debug, io::stdin().lock(), io::stdout())?;
+
+    // Only games played by a difficulty's own rules are comparable.
+    if state == State::Won && game.rules().is_preset() {
+        let score = Score {
+            name: config.name,
+            difficulty: game.difficulty(),
+            attempts: game.attempts(),
+            duration: start.elapsed(),
+        };
+        // Losing the score shouldn't spoil the win.
+        match &path {
+            Some(path) => {
+                if let Err(e) = scores::append(path, &score) {
+                    eprintln!("Couldn't save your score to {}: {e}", path.display());
+                }
+            }
+            None => eprintln!("Couldn't save your score: neither XDG_DATA_HOME nor HOME is set"),
+        }
+    }
+
+    Ok(())
+}
+
+fn warn_skipped(path: &Path, skipped: usize) {
+    if skipped > 0 {
+        eprintln!(
+            "Skipped {skipped} unreadable line{} in {}",
+            if skipped == 1 { "" } else { "s" },
+            path.display()
+        );
+    }
+}
+
+// Plays `game` in the terminal, reading guesses from `input` until it's
+// finished or the input ends. Returns how far it got.
+pub fn play(
+    game: &mut Game,
+    debug: bool,
+    mut input: impl BufRead,
+    mut output: impl Write,
+) -> io::Result<State> {
+    let rules = game.rules().clone();
+    let (low, high) = (rules.format(rules.low), rules.format(rules.high));
+
+    writeln!(output, "Guess the number!")?;
+    writeln!(
+        output,
+        "It's between {low} and {high}, and you have {} attempts.",
+        game.attempts_left()
+    )?;
+    if rules.precision > 0 {
+        writeln!(
+            output,
+            "Guesses can have {} decimal places, and any within {} of it win.",
+            rules.precision,
+            rules.format(rules.tolerance)
+        )?;
+    }
+
+    if debug {
+        writeln!(
+            output,
+            "the secret number is: {}",
+            rules.format(game.secret())
+        )?;
+    }
+
+    while !game.is_finished() {
+        writeln!(output, "Please input your guess.")?;
+
+        let mut guess: String = String::new();
+
+        if input.read_line(&mut guess)? == 0 {
+            writeln!(
+                output,
+                "\nNo more guesses; the secret number was {}.",
+                rules.format(game.secret())
+            )?;
+            return Ok(game.state());
+        }
+
+        let guess = guess.trim();
+        let guess = match rules.parse(guess) {
+            Ok(num) => num,
+            Err(BadNumber::TooLarge) => {
+                writeln!(output, "{guess} is out of range.")?;
+                continue;
+            }
+            Err(BadNumber::TooPrecise) if rules.precision == 0 => {
+                writeln!(output, "'{guess}' is not a whole number.")?;
+                continue;
+            }
+            Err(BadNumber::TooPrecise) => {
+                writeln!(
+                    output,
+                    "'{guess}' has more than {} decimal places.",
+                    rules.precision
+                )?;
+                continue;
+            }
+            Err(BadNumber::NotANumber) => {
+                writeln!(output, "'{guess}' is not a number.")?;
+                continue;
+            }
+        };
+
+        writeln!(output, "You guessed: {}", rules.format(guess))?;
+
+        match game.guess(guess) {
+            Feedback::TooSmall => writeln!(output, "Too small!")?,
+            Feedback::TooBig => writeln!(output, "Too big!")?,
+            Feedback::Correct => {
+                writeln!(output, "You win!")?;
+                continue;
+            }
+            Feedback::OutOfRange => {
+                writeln!(
+                    output,
+                    "{} is out of range: guess between {low} and {high}.",
+                    rules.format(guess)
+                )?;
+                continue;
+            }
+            Feedback::GameOver => continue,
+        }
+
+        match game.hint() {
+            Some(Hint::Warmer) => writeln!(output, "Warmer.")?,
+            Some(Hint::Colder) => writeln!(output, "Colder.")?,
+            Some(Hint::Same) => writeln!(output, "Neither warmer nor colder.")?,
+            None => {}
+        }
+    }
+
+    if game.state() == State::Lost {
+        writeln!(
+            output,
+            "Out of attempts! The secret number was {}. You lose.",
+            rules.format(game.secret())
+        )?;
+    }
+
+    Ok(game.state())
+}
+
+// Plays a game of bulls and cows in the terminal, like `play`.
+pub fn play_bulls_cows(
+    game: &mut BullsCows,
+    debug: bool,
+    mut input: impl BufRead,
+    mut output: impl Write,
+) -> io::Result<State> {
+    writeln!(output, "Guess the code!")?;
+    writeln!(
+        output,
+        "It's {} different digits, and you have {} attempts.",
+        bulls_cows::DIGITS,
+        game.attempts_left()
+    )?;
+
+    if debug {
+        writeln!(output, "the secret code is: {}", game.secret())?;
+    }
+
+    while !game.is_finished() {
+        writeln!(output, "Please input your guess.")?;
+
+        let mut guess = String::new();
+        if input.read_line(&mut guess)? == 0 {
+            writeln!(
+                output,
+                "\nNo more guesses; the secret code was {}.",
+                game.secret()
+            )?;
+            return Ok(game.state());
+        }
+
+        let guess: Code = match guess.trim().parse() {
+            Ok(code) => code,
+            Err(e) => {
+                writeln!(output, "{e}.")?;
+                continue;
+            }
+        };
+
+        let Some(response) = game.guess(guess) else {
+            break;
+        };
+        if response.is_correct() {
+            writeln!(output, "You win!")?;
+        } else {
+            writeln!(output, "{response}")?;
+        }
+    }
+
+    if game.state() == State::Lost {
+        writeln!(
+            output,
+            "Out of attempts! The secret code was {}. You lose.",
+            game.secret()
+        )?;
+    }
+
+    Ok(game.state())
+}
+
+// Lets the code solver play `game`, showing each guess and its response.
+pub fn auto_bulls_cows(game: &mut BullsCows, mut output: impl Write) -> io::Result<State> {
+    let mut solver = CodeSolver::new();
+
+    writeln!(
+        output,
+        "Guessing a code of {} different digits, in {} attempts.",
+        bulls_cows::DIGITS,
+        game.attempts_left()
+    )?;
+
+    while !game.is_finished() {
+        let Some(guess) = solver.guess() else { break };
+        let Some(response) = game.guess(guess) else {
+            break;
+        };
+        writeln!(output, "Guess {}: {guess} has {response}", game.attempts())?;
+        solver.learn(guess, response);
+    }
+
+    match game.state() {
+        State::Won => writeln!(
+            output,
+            "Found {} in {} attempts.",
+            game.secret(),
+            game.attempts()
+        )?,
+        _ => writeln!(
+            output,
+            "Out of attempts! The secret code was {}.",
+            game.secret()
+        )?,
+    }
+
+    Ok(game.state())
+}
+
+// Lets a solver play `game`, showing each guess and how it compared with the
+// secret.
+pub fn auto(
+    game: &mut Game,
+    strategy: Strategy,
+    rng: &mut impl Rng,
+    mut output: impl Write,
+) -> io::Result<State> {
+    let rules = game.rules().clone();
+    let mut solver = Solver::new(strategy, rules.range());
+
+    writeln!(
+        output,
+        "Guessing a number between {} and {} with {strategy} search, in {} attempts.",
+        rules.format(rules.low),
+        rules.format(rules.high),
+        game.attempts_left()
+    )?;
+
+    while !game.is_finished() {
+        let guess = solver.guess(rng);
+        let Some(ordering) = game.guess(guess).ordering() else {
+            break;
+        };
+        writeln!(
+            output,
+            "Guess {}: {} is {ordering:?}",
+            game.attempts(),
+            rules.format(guess)
+        )?;
+        solver.learn(guess, ordering);
+    }
+
+    match game.state() {
+        State::Won => writeln!(
+            output,
+            "Found {} in {} attempts.",
+            rules.format(game.secret()),
+            game.attempts()
+        )?,
+        _ => writeln!(
+            output,
+            "Out of attempts! The secret number was {}.",
+            rules.format(game.secret())
+        )?,
+    }
+
+    Ok(game.state())
+}
+
+// Guesses the number the player is thinking of within `rules`, reading their
+// answers from `input`. Returns the number, or `None` if the answers
+// contradicted each other or the input ended first.
+pub fn reverse(
+    rules: &Rules,
+    strategy: Strategy,
+    rng: &mut impl Rng,
+    mut input: impl BufRead,
+    mut output: impl Write,
+) -> io::Result<Option<i64>> {
+    let mut solver = Solver::new(strategy, rules.range());
+    // The closest guesses the player said were below and above their number.
+    let mut above: Option<i64> = None;
+    let mut below: Option<i64> = None;
+    let (low, high) = (rules.format(rules.low), rules.format(rules.high));
+
+    writeln!(
+        output,
+        "Think of a number between {low} and {high}, and I'll guess it."
+    )?;
+
+    let mut attempts = 0;
+    let mut guess = solver.guess(rng);
+    attempts += 1;
+
+    loop {
+        writeln!(
+            output,
+            "Is it {}? (higher, lower or correct)",
+            rules.format(guess)
+        )?;
+
+        let mut answer = String::new();
+        if input.read_line(&mut answer)? == 0 {
+            return Ok(None);
+        }
+
+        // Compared the same way as a player's guess: `Less` means the guess
+        // is below the secret.
+        let ordering = match answer.trim().to_lowercase().as_str() {
+            "higher" | "h" => Ordering::Less,
+            "lower" | "l" => Ordering::Greater,
+            "correct" | "c" | "yes" | "y" => Ordering::Equal,
+            _ => {
+                writeln!(output, "Please answer higher, lower or correct.")?;
+                continue;
+            }
+        };
+
+        match ordering {
+            Ordering::Less => above = Some(above.map_or(guess, |n| n.max(guess))),
+            Ordering::Greater => below = Some(below.map_or(guess, |n| n.min(guess))),
+            Ordering::Equal => {
+                writeln!(
+                    output,
+                    "Got it: {}, in {attempts} attempts.",
+                    rules.format(guess)
+                )?;
+                return Ok(Some(guess));
+            }
+        }
+
+        solver.learn(guess, ordering);
+        if !solver.is_consistent() {
+            let (above, below) = (
+                above.map(|n| rules.format(n)),
+                below.map(|n| rules.format(n)),
+            );
+            let reason = match (above, below) {
+                (Some(above), Some(below)) => {
+                    format!("you said it's higher than {above} and lower than {below}")
+                }
+                (Some(above), None) => format!("you said it's higher than {above}"),
+                (None, Some(below)) => format!("you said it's lower than {below}"),
+                (None, None) => unreachable!("only answers narrow the range"),
+            };
+            writeln!(
+                output,
+                "That can't be right: {reason}, but it's between {low} and {high}."
+            )?;
+            return Ok(None);
+        }
+
+        guess = solver.guess(rng);
+        attempts += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The secret number a game seeded with `seed` will pick.
+    fn secret(rules: &Rules, seed: u64) -> i64 {
+        Game::new(rules.clone(), &mut StdRng::seed_from_u64(seed)).secret()
+    }
+
+    // Plays a seeded game with the given lines as input.
+    fn play_script(rules: &Rules, seed: u64, debug: bool, script: &str) -> (State, String) {
+        let mut game = Game::new(rules.clone(), &mut StdRng::seed_from_u64(seed));
+        let mut output = Vec::new();
+        let state = play(&mut game, debug, script.as_bytes(), &mut output).unwrap();
+        (state, String::from_utf8(output).unwrap())
+    }
+
+    #[test]
+    fn seeded_games_pick_the_same_secret() {
+        for seed in 0..20 {
+            let first = Game::new(
+                Rules::new(Difficulty::Hard),
+                &mut StdRng::seed_from_u64(seed),
+            );
+            let second = Game::new(
+                Rules::new(Difficulty::Hard),
+                &mut StdRng::seed_from_u64(seed),
+            );
+            assert_eq!(first.secret(), second.secret());
+            assert!(Difficulty::Hard.range().contains(&first.secret()));
+        }
+    }
+
+    #[test]
+    fn debug_shows_the_seeded_secret() {
+        let secret = secret(&Rules::new(Difficulty::Medium), 7);
+
+        let (_, output) = play_script(
+            &Rules::new(Difficulty::Medium),
+            7,
+            true,
+            &format!("{secret}\n"),
+        );
+
+        assert!(output.contains(&format!("the secret number is: {secret}\n")));
+    }
+
+    #[test]
+    fn scripted_game_is_won() {
+        let secret = secret(&Rules::new(Difficulty::Hard), 42);
+        let script = format!("{}\nnope\n{}\n{secret}\n", secret - 1, secret + 1);
+
+        let (state, output) = play_script(&Rules::new(Difficulty::Hard), 42, false, &script);
+
+        assert_eq!(state, State::Won);
+        assert!(!output.contains("the secret number is"));
+        let feedback: Vec<&str> = output
+            .lines()
+            .filter(|line| line.starts_with("Too") || line.starts_with("You win"))
+            .collect();
+        assert_eq!(feedback, ["Too small!", "Too big!", "You win!"]);
+    }
+
+    #[test]
+    fn rejected_input_is_explained_and_not_counted() {
+        let secret = secret(&Rules::new(Difficulty::Easy), 5);
+        let script = format!("ten\n2.5\n-3\n99999999999999999999\n{secret}\n");
+
+        let (state, output) = play_script(&Rules::new(Difficulty::Easy), 5, false, &script);
+
+        assert_eq!(state, State::Won);
+        assert!(output.contains("'ten' is not a number.\n"));
+        assert!(output.contains("'2.5' is not a whole number.\n"));
+        assert!(output.contains("-3 is out of range: guess between 1 and 10.\n"));
+        assert!(output.contains("99999999999999999999 is out of range.\n"));
+        assert!(output.ends_with("You win!\n"));
+    }
+
+    #[test]
+    fn precision_games_take_decimal_guesses() {
+        let rules = Rules::build(
+            Difficulty::Easy,
+            Some("-1"),
+            Some("1"),
+            Some(2),
+            Some("0.05"),
+        )
+        .unwrap();
+        let secret = secret(&rules, 4);
+        // Off by the whole tolerance, still inside the range.
+        let close = rules.format(if secret + 5 <= rules.high {
+            secret + 5
+        } else {
+            secret - 5
+        });
+        let script = format!("0.125\n{close}\n");
+
+        let (state, output) = play_script(&rules, 4, false, &script);
+
+        assert_eq!(state, State::Won);
+        assert!(output.contains("It's between -1.00 and 1.00, and you have 7 attempts.\n"));
+        assert!(output.contains("'0.125' has more than 2 decimal places.\n"));
+        assert!(output.contains(&format!("You guessed: {close}\nYou win!\n")));
+    }
+
+    #[test]
+    fn end_of_input_stops_the_game() {
+        let secret = secret(&Rules::new(Difficulty::Hard), 1);
+        let wrong = if secret == 1 { 2 } else { 1 };
+
+        let (state, output) = play_script(
+            &Rules::new(Difficulty::Hard),
+            1,
+            false,
+            &format!("{wrong}\n"),
+        );
+
+        assert_eq!(state, State::Playing);
+        assert!(output.ends_with(&format!(
+            "\nNo more guesses; the secret number was {secret}.\n"
+        )));
+    }
+
+    #[test]
+    fn hints_follow_the_second_guess() {
+        let secret = secret(&Rules::new(Difficulty::Hard), 9);
+        // Moving towards the secret from the far end of the range.
+        let (far, near) = if secret > 500 {
+            (1, secret - 1)
+        } else {
+            (1000, secret + 1)
+        };
+        let script = format!("{far}\n{near}\n{far}\n{secret}\n");
+
+        let (_, output) = play_script(&Rules::new(Difficulty::Hard), 9, false, &script);
+
+        let hints: Vec<&str> = output
+            .lines()
+            .filter(|line| line.ends_with("er."))
+            .collect();
+        assert_eq!(hints, ["Warmer.", "Colder."]);
+    }
+
+    #[test]
+    fn auto_shows_each_guess() {
+        let mut game = Game::with_secret(Rules::new(Difficulty::Medium), 30);
+        let mut output = Vec::new();
+        let mut rng = StdRng::seed_from_u64(0);
+
+        let state = auto(&mut game, Strategy::BinarySearch, &mut rng, &mut output).unwrap();
+
+        assert_eq!(state, State::Won);
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "\
+Guessing a number between 1 and 100 with binary search, in 8 attempts.
+Guess 1: 50 is Greater
+Guess 2: 25 is Less
+Guess 3: 37 is Greater
+Guess 4: 31 is Greater
+Guess 5: 28 is Less
+Guess 6: 29 is Less
+Guess 7: 30 is Equal
+Found 30 in 7 attempts.
+"
+        );
+    }
+
+    // Plays the reverse game with the given answers.
+    fn reverse_script(script: &str) -> (Option<i64>, String) {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut output = Vec::new();
+        let found = reverse(
+            &Rules::new(Difficulty::Medium),
+            Strategy::BinarySearch,
+            &mut rng,
+            script.as_bytes(),
+            &mut output,
+        )
+        .unwrap();
+        (found, String::from_utf8(output).unwrap())
+    }
+
+    #[test]
+    fn reverse_finds_the_players_number() {
+        let (found, output) = reverse_script("lower\nwhat\nh\nHigher\ncorrect\n");
+
+        assert_eq!(found, Some(43));
+        assert_eq!(
+            output,
+            "\
+Think of a number between 1 and 100, and I'll guess it.
+Is it 50? (higher, lower or correct)
+Is it 25? (higher, lower or correct)
+Please answer higher, lower or correct.
+Is it 25? (higher, lower or correct)
+Is it 37? (higher, lower or correct)
+Is it 43? (higher, lower or correct)
+Got it: 43, in 4 attempts.
+"
+        );
+    }
+
+    #[test]
+    fn reverse_detects_contradictions() {
+        // Narrows down to 55 and then says it's lower than that.
+        let (found, output) = reverse_script("h\nl\nl\nl\nh\nh\nl\n");
+
+        assert_eq!(found, None);
+        assert!(output.ends_with(
+            "That can't be right: you said it's higher than 54 and lower than 55, \
+             but it's between 1 and 100.\n"
+        ));
+    }
+
+    #[test]
+    fn reverse_detects_answers_outside_the_range() {
+        let (found, output) = reverse_script(&"h\n".repeat(8));
+
+        assert_eq!(found, None);
+        assert!(output.ends_with(
+            "That can't be right: you said it's higher than 100, but it's between 1 and 100.\n"
+        ));
+    }
+
+    #[test]
+    fn bulls_and_cows_game() {
+        let mut game = BullsCows::with_secret("4271".parse().unwrap());
+        let mut output = Vec::new();
+        let script = "1234\n4471\n4217\n4271\n";
+
+        let state = play_bulls_cows(&mut game, false, script.as_bytes(), &mut output).unwrap();
+
+        assert_eq!(state, State::Won);
+        assert_eq!(game.attempts(), 3);
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "\
+Guess the code!
+It's 4 different digits, and you have 10 attempts.
+Please input your guess.
+1 bull, 2 cows
+Please input your guess.
+'4471' isn't 4 different digits.
+Please input your guess.
+2 bulls, 2 cows
+Please input your guess.
+You win!
+"
+        );
+    }
+
+    #[test]
+    fn bulls_and_cows_solver() {
+        let mut game = BullsCows::new(&mut StdRng::seed_from_u64(8));
+        let mut output = Vec::new();
+
+        let state = auto_bulls_cows(&mut game, &mut output).unwrap();
+
+        let output = String::from_utf8(output).unwrap();
+        assert_eq!(state, State::Won);
+        assert!(output.starts_with(
+            "Guessing a code of 4 different digits, in 10 attempts.\nGuess 1: 0123 has "
+        ));
+        assert!(output.ends_with(&format!(
+            "Found {} in {} attempts.\n",
+            game.secret(),
+            game.attempts()
+        )));
+    }
+
+    #[test]
+    fn scripted_game_is_lost_when_attempts_run_out() {
+        let secret = secret(&Rules::new(Difficulty::Easy), 3);
+        let wrong = if secret == 1 { 2 } else { 1 };
+        let script = format!("{wrong}\n").repeat(5);
+
+        let (state, output) = play_script(&Rules::new(Difficulty::Easy), 3, false, &script);
+
+        assert_eq!(state, State::Lost);
+        assert_eq!(output.matches("You guessed").count(), 5);
+        assert!(output.ends_with(&format!(
+            "Out of attempts! The secret number was {secret}. You lose.\n"
+        )));
+    }
+}
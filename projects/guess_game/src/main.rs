@@ -1,41 +1,15 @@
-use rand::Rng;
-use std::{string::String, io};
-use std::cmp::Ordering;
-// use std::string::String;
-// use std::io;
+use std::{env, process};
 
-fn main() {
-    println!("Guess the number!");
-
-    let secret_number = rand::thread_rng().gen_range(1..101);
-
-    println!("the secret number is: {}", secret_number);
-
-    loop {
-
-        println!("Please input your guess.");
-
-        let mut guess: String = String::new();
-
-        io::stdin()
-            .read_line(&mut guess)
-            .expect("Failed to read line");
-
-        let guess: u32 = match guess.trim().parse() {
-            Ok(num) => num,
-            Err(_) => continue,
-        };
-
-        println!("You guessed: {}", guess);
-
-        match guess.cmp(&secret_number) {
-            Ordering::Less => println!("Too small!"),
-            Ordering::Greater => println!("Too big!"),
-            Ordering::Equal => {
-                println!("You win!");
-                break;
-            }
-        }
+use guess_game::Config;
 
+fn main() {
+    let config = Config::build(env::args()).unwrap_or_else(|err| {
+        eprintln!("Problem parsing arguments: {err}");
+        process::exit(1);
+    });
+
+    if let Err(e) = guess_game::run(config) {
+        eprintln!("Application error: {e}");
+        process::exit(1);
     }
 }
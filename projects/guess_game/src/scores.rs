@@ -0,0 +1,231 @@
+use std::env;
+use std::fmt;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::Duration;
+
+use crate::game::Difficulty;
+
+// One won game, stored as a tab-separated line:
+// `name<TAB>difficulty<TAB>attempts<TAB>milliseconds`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Score {
+    pub name: String,
+    pub difficulty: Difficulty,
+    pub attempts: u32,
+    pub duration: Duration,
+}
+
+impl fmt::Display for Score {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // Tabs and newlines would break the line apart.
+        let name: String = self
+            .name
+            .chars()
+            .map(|c| if c.is_control() { ' ' } else { c })
+            .collect();
+        write!(
+            f,
+            "{name}\t{}\t{}\t{}",
+            self.difficulty,
+            self.attempts,
+            self.duration.as_millis()
+        )
+    }
+}
+
+impl FromStr for Score {
+    type Err = String;
+
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let fields: Vec<&str> = line.split('\t').collect();
+        let [name, difficulty, attempts, millis] = fields[..] else {
+            return Err(format!("expected 4 fields, found {}", fields.len()));
+        };
+
+        Ok(Score {
+            name: name.to_string(),
+            difficulty: difficulty.parse()?,
+            attempts: attempts
+                .parse()
+                .map_err(|_| format!("invalid attempts '{attempts}'"))?,
+            duration: Duration::from_millis(
+                millis
+                    .parse()
+                    .map_err(|_| format!("invalid duration '{millis}'"))?,
+            ),
+        })
+    }
+}
+
+// The scores read from a file, and how many of its lines couldn't be read.
+#[derive(Debug, Default)]
+pub struct Scores {
+    pub scores: Vec<Score>,
+    pub skipped: usize,
+}
+
+// `$XDG_DATA_HOME/guess_game/scores`, or `~/.local/share/guess_game/scores`.
+pub fn path() -> Option<PathBuf> {
+    let data = env::var_os("XDG_DATA_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".local/share")))?;
+    Some(data.join("guess_game").join("scores"))
+}
+
+// Reads every score in `path`. A missing file has no scores, and lines that
+// don't parse are skipped rather than losing the rest of the table.
+pub fn load(path: &Path) -> io::Result<Scores> {
+    let contents = match fs::read(path) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Scores::default()),
+        Err(e) => return Err(e),
+    };
+
+    let mut scores = Scores::default();
+    for line in String::from_utf8_lossy(&contents).lines() {
+        if line.is_empty() {
+            continue;
+        }
+        match line.parse() {
+            Ok(score) => scores.scores.push(score),
+            Err(_) => scores.skipped += 1,
+        }
+    }
+
+    Ok(scores)
+}
+
+pub fn append(path: &Path, score: &Score) -> io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{score}")
+}
+
+// Fewest attempts first, then the fastest, for each difficulty that has any
+// scores.
+pub fn leaderboard(scores: &[Score]) -> String {
+    let mut out = String::new();
+
+    for difficulty in Difficulty::ALL {
+        let mut ranked: Vec<&Score> = scores
+            .iter()
+            .filter(|score| score.difficulty == difficulty)
+            .collect();
+        if ranked.is_empty() {
+            continue;
+        }
+        ranked.sort_by_key(|score| (score.attempts, score.duration));
+
+        if !out.is_empty() {
+            out.push('\n');
+        }
+        out.push_str(&format!("{difficulty}:\n"));
+        for (i, score) in ranked.iter().enumerate() {
+            out.push_str(&format!(
+                "{:>3}. {:<16} {:>2} attempts  {:>6.1}s\n",
+                i + 1,
+                score.name,
+                score.attempts,
+                score.duration.as_secs_f64()
+            ));
+        }
+    }
+
+    if out.is_empty() {
+        out.push_str("No scores yet.\n");
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn score(name: &str, difficulty: Difficulty, attempts: u32, millis: u64) -> Score {
+        Score {
+            name: name.to_string(),
+            difficulty,
+            attempts,
+            duration: Duration::from_millis(millis),
+        }
+    }
+
+    #[test]
+    fn scores_round_trip_through_lines() {
+        let original = score("ferris", Difficulty::Hard, 9, 12_345);
+
+        assert_eq!(original.to_string(), "ferris\thard\t9\t12345");
+        assert_eq!(original.to_string().parse(), Ok(original));
+    }
+
+    #[test]
+    fn names_cannot_break_lines() {
+        let tricky = score("a\tb\nc", Difficulty::Easy, 1, 0);
+
+        assert_eq!(tricky.to_string(), "a b c\teasy\t1\t0");
+    }
+
+    #[test]
+    fn corrupt_lines_are_skipped() {
+        let path = env::temp_dir().join(format!("guess_game-scores-{}", std::process::id()));
+        fs::write(
+            &path,
+            b"ann\teasy\t3\t1500\ngarbage\nbob\tlegendary\t1\t1\n\xff\xfe\ncat\thard\t8\t900\n",
+        )
+        .unwrap();
+
+        let scores = load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(
+            scores.scores,
+            [
+                score("ann", Difficulty::Easy, 3, 1500),
+                score("cat", Difficulty::Hard, 8, 900)
+            ]
+        );
+        assert_eq!(scores.skipped, 3);
+    }
+
+    #[test]
+    fn missing_file_has_no_scores() {
+        let scores = load(Path::new("/nonexistent/guess_game/scores")).unwrap();
+
+        assert!(scores.scores.is_empty());
+        assert_eq!(scores.skipped, 0);
+    }
+
+    #[test]
+    fn leaderboard_ranks_each_difficulty() {
+        let scores = [
+            score("slow", Difficulty::Medium, 4, 9_000),
+            score("hard", Difficulty::Hard, 10, 60_000),
+            score("fast", Difficulty::Medium, 4, 2_500),
+            score("best", Difficulty::Medium, 3, 20_000),
+        ];
+
+        assert_eq!(
+            leaderboard(&scores),
+            "\
+medium:
+  1. best              3 attempts    20.0s
+  2. fast              4 attempts     2.5s
+  3. slow              4 attempts     9.0s
+
+hard:
+  1. hard             10 attempts    60.0s
+"
+        );
+    }
+
+    #[test]
+    fn empty_leaderboard() {
+        assert_eq!(leaderboard(&[]), "No scores yet.\n");
+    }
+}
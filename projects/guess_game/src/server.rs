@@ -0,0 +1,379 @@
+// Several players race to guess the same secret number over TCP.
+//
+// The protocol is line based. On connecting, a player gets
+// `WELCOME <lowest> <highest>` and can then send:
+//
+//   NAME <name>    how the player is announced, without spaces; replied to
+//                  with `OK`
+//   GUESS <n>      or just `<n>`; replied to with `TOO SMALL`, `TOO BIG`
+//                  or `CORRECT`, followed by `LOST` on running out of attempts
+//   QUIT           leaves the game and disconnects
+//
+// Anything else gets `ERROR <reason>`. When someone wins, every player is
+// sent `WINNER <name> <secret>` and disconnected.
+
+use rand::Rng;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{Shutdown, SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use crate::game::{BadNumber, Feedback, Game, Rules, State};
+
+// How long a write may block before the player is given up on. Writes happen
+// with the shared state locked, so a player who stops reading their replies
+// must not hold up everyone else for long.
+const WRITE_TIMEOUT: Duration = Duration::from_secs(5);
+
+pub struct Server {
+    listener: TcpListener,
+    rules: Rules,
+    secret: i64,
+}
+
+// What every player's connection shares.
+struct Shared {
+    // Each connected player, by the address they connected from.
+    players: Vec<(SocketAddr, TcpStream)>,
+    winner: Option<String>,
+}
+
+impl Shared {
+    fn broadcast(&mut self, line: &str) {
+        // A player who has gone away just misses the news.
+        for (_, player) in &mut self.players {
+            let _ = writeln!(player, "{line}");
+        }
+    }
+
+    // Forgets a player who has left, hanging up on them if they haven't already.
+    fn leave(&mut self, addr: SocketAddr) {
+        self.players.retain(|(player, stream)| {
+            if *player != addr {
+                return true;
+            }
+            let _ = stream.shutdown(Shutdown::Both);
+            false
+        });
+    }
+}
+
+impl Server {
+    pub fn bind(addr: impl ToSocketAddrs, rules: Rules, rng: &mut impl Rng) -> io::Result<Server> {
+        Ok(Server {
+            listener: TcpListener::bind(addr)?,
+            secret: rng.gen_range(rules.range()),
+            rules,
+        })
+    }
+
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.listener.local_addr()
+    }
+
+    pub fn secret(&self) -> i64 {
+        self.secret
+    }
+
+    pub fn rules(&self) -> &Rules {
+        &self.rules
+    }
+
+    // Serves players until one of them wins, and returns the winner's name.
+    pub fn run(self) -> io::Result<String> {
+        let shared = Arc::new(Mutex::new(Shared {
+            players: Vec::new(),
+            winner: None,
+        }));
+        let (won, winner) = mpsc::channel();
+        let Server {
+            listener,
+            rules,
+            secret,
+        } = self;
+
+        thread::spawn(move || {
+            for (i, stream) in listener.incoming().enumerate() {
+                let Ok(stream) = stream else { continue };
+                let shared = Arc::clone(&shared);
+                let won = won.clone();
+                let name = format!("player{}", i + 1);
+                let game = Game::with_secret(rules.clone(), secret);
+                thread::spawn(move || {
+                    let _ = serve(stream, name, game, &shared, &won);
+                });
+            }
+        });
+
+        winner
+            .recv()
+            .map_err(|_| io::Error::other("stopped accepting players"))
+    }
+}
+
+// Serves one player's connection until they leave or someone wins.
+fn serve(
+    stream: TcpStream,
+    name: String,
+    game: Game,
+    shared: &Mutex<Shared>,
+    won: &Sender<String>,
+) -> io::Result<()> {
+    // Replies are tiny; don't hold them back waiting for more.
+    stream.set_nodelay(true)?;
+    stream.set_write_timeout(Some(WRITE_TIMEOUT))?;
+    let addr = stream.peer_addr()?;
+    {
+        let mut shared = shared.lock().unwrap();
+        if let Some(winner) = &shared.winner {
+            let secret = game.rules().format(game.secret());
+            return writeln!(&stream, "WINNER {winner} {secret}");
+        }
+        shared.players.push((addr, stream.try_clone()?));
+    }
+
+    let result = play(&stream, name, game, shared, won);
+    shared.lock().unwrap().leave(addr);
+    result
+}
+
+// Answers the player's commands until they quit, hang up or someone wins.
+fn play(
+    stream: &TcpStream,
+    mut name: String,
+    mut game: Game,
+    shared: &Mutex<Shared>,
+    won: &Sender<String>,
+) -> io::Result<()> {
+    let mut out = stream;
+    let rules = game.rules().clone();
+
+    writeln!(
+        out,
+        "WELCOME {} {}",
+        rules.format(rules.low),
+        rules.format(rules.high)
+    )?;
+
+    for line in BufReader::new(stream).lines() {
+        let line = line?;
+        let line = line.trim();
+        let (command, argument) = line.split_once(' ').unwrap_or((line, ""));
+        let argument = argument.trim();
+
+        let guess = match command.to_uppercase().as_str() {
+            "NAME" if argument.contains(char::is_whitespace) => {
+                // `WINNER <name> <secret>` couldn't be split up otherwise.
+                writeln!(out, "ERROR a name can't contain spaces")?;
+                continue;
+            }
+            "NAME" if !argument.is_empty() => {
+                name = argument.to_string();
+                writeln!(out, "OK")?;
+                continue;
+            }
+            "NAME" => {
+                writeln!(out, "ERROR a name is needed")?;
+                continue;
+            }
+            "QUIT" => break,
+            "GUESS" => argument,
+            _ if rules.parse(line) != Err(BadNumber::NotANumber) => line,
+            _ => {
+                writeln!(out, "ERROR unknown command '{command}'")?;
+                continue;
+            }
+        };
+
+        // Held while guessing, so only one player can win.
+        let mut shared = shared.lock().unwrap();
+        if shared.winner.is_some() {
+            break;
+        }
+
+        let feedback = match rules.parse(guess) {
+            Ok(guess) => game.guess(guess),
+            Err(BadNumber::TooLarge) => Feedback::OutOfRange,
+            Err(BadNumber::TooPrecise) => {
+                writeln!(out, "ERROR '{guess}' has too many decimal places")?;
+                continue;
+            }
+            Err(BadNumber::NotANumber) => {
+                writeln!(out, "ERROR '{guess}' is not a number")?;
+                continue;
+            }
+        };
+
+        match feedback {
+            Feedback::TooSmall => writeln!(out, "TOO SMALL")?,
+            Feedback::TooBig => writeln!(out, "TOO BIG")?,
+            Feedback::Correct => {
+                writeln!(out, "CORRECT")?;
+                shared.winner = Some(name.clone());
+                shared.broadcast(&format!("WINNER {name} {}", rules.format(game.secret())));
+                for (_, player) in &shared.players {
+                    let _ = player.shutdown(Shutdown::Both);
+                }
+                let _ = won.send(name);
+                return Ok(());
+            }
+            Feedback::OutOfRange => writeln!(
+                out,
+                "ERROR out of range: guess between {} and {}",
+                rules.format(rules.low),
+                rules.format(rules.high)
+            )?,
+            Feedback::GameOver => writeln!(out, "ERROR out of attempts")?,
+        }
+
+        // Only the guess that used up the last attempt loses.
+        if feedback.ordering().is_some() && game.state() == State::Lost {
+            writeln!(out, "LOST")?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::Difficulty;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use std::thread::JoinHandle;
+
+    // Starts a seeded server on a free loopback port.
+    fn start(rules: Rules) -> (SocketAddr, i64, JoinHandle<io::Result<String>>) {
+        let mut rng = StdRng::seed_from_u64(11);
+        let server = Server::bind("127.0.0.1:0", rules, &mut rng).unwrap();
+        let addr = server.local_addr().unwrap();
+        let secret = server.secret();
+        (addr, secret, thread::spawn(move || server.run()))
+    }
+
+    struct Client {
+        reader: BufReader<TcpStream>,
+        writer: TcpStream,
+    }
+
+    impl Client {
+        fn connect(addr: SocketAddr) -> Client {
+            let stream = TcpStream::connect(addr).unwrap();
+            stream.set_nodelay(true).unwrap();
+            Client {
+                writer: stream.try_clone().unwrap(),
+                reader: BufReader::new(stream),
+            }
+        }
+
+        fn send(&mut self, line: &str) {
+            writeln!(self.writer, "{line}").unwrap();
+        }
+
+        fn receive(&mut self) -> String {
+            let mut line = String::new();
+            self.reader.read_line(&mut line).unwrap();
+            line.trim_end().to_string()
+        }
+
+        // Sends a line and returns the reply.
+        fn ask(&mut self, line: &str) -> String {
+            self.send(line);
+            self.receive()
+        }
+    }
+
+    #[test]
+    fn first_correct_guess_wins_for_everyone() {
+        let (addr, secret, server) = start(Rules::new(Difficulty::Medium));
+        let mut ann = Client::connect(addr);
+        let mut bob = Client::connect(addr);
+
+        assert_eq!(ann.receive(), "WELCOME 1 100");
+        assert_eq!(bob.receive(), "WELCOME 1 100");
+        assert_eq!(ann.ask("NAME ann"), "OK");
+        assert_eq!(bob.ask("NAME bob"), "OK");
+
+        assert_eq!(ann.ask(&format!("GUESS {}", secret + 1)), "TOO BIG");
+        assert_eq!(bob.ask(&(secret - 1).to_string()), "TOO SMALL");
+        assert_eq!(bob.ask(&secret.to_string()), "CORRECT");
+
+        let winner = format!("WINNER bob {secret}");
+        assert_eq!(bob.receive(), winner);
+        assert_eq!(ann.receive(), winner);
+        assert_eq!(ann.receive(), "", "ann should be disconnected");
+        assert_eq!(server.join().unwrap().unwrap(), "bob");
+
+        // Anyone turning up late only hears who won.
+        let mut late = Client::connect(addr);
+        assert_eq!(late.receive(), winner);
+    }
+
+    #[test]
+    fn bad_lines_are_explained() {
+        let (addr, secret, server) = start(Rules::new(Difficulty::Easy));
+        let mut player = Client::connect(addr);
+
+        assert_eq!(player.receive(), "WELCOME 1 10");
+        assert_eq!(player.ask("NAME"), "ERROR a name is needed");
+        assert_eq!(
+            player.ask("NAME ann bob"),
+            "ERROR a name can't contain spaces"
+        );
+        assert_eq!(player.ask("HELLO"), "ERROR unknown command 'HELLO'");
+        assert_eq!(player.ask("GUESS ten"), "ERROR 'ten' is not a number");
+        assert_eq!(
+            player.ask("GUESS 11"),
+            "ERROR out of range: guess between 1 and 10"
+        );
+        assert_eq!(
+            player.ask("-4"),
+            "ERROR out of range: guess between 1 and 10"
+        );
+
+        assert_eq!(player.ask(&secret.to_string()), "CORRECT");
+        assert_eq!(player.receive(), format!("WINNER player1 {secret}"));
+        assert_eq!(server.join().unwrap().unwrap(), "player1");
+    }
+
+    #[test]
+    fn quitting_disconnects() {
+        let (addr, secret, server) = start(Rules::new(Difficulty::Easy));
+        let mut quitter = Client::connect(addr);
+        let mut stayer = Client::connect(addr);
+        quitter.receive();
+        stayer.receive();
+
+        quitter.send("QUIT");
+        assert_eq!(quitter.receive(), "", "the quitter should be disconnected");
+
+        assert_eq!(stayer.ask(&secret.to_string()), "CORRECT");
+        assert_eq!(stayer.receive(), format!("WINNER player2 {secret}"));
+        assert_eq!(server.join().unwrap().unwrap(), "player2");
+    }
+
+    #[test]
+    fn running_out_of_attempts_loses() {
+        let (addr, secret, server) = start(Rules::new(Difficulty::Easy));
+        let mut loser = Client::connect(addr);
+        let mut winner = Client::connect(addr);
+        loser.receive();
+        winner.receive();
+
+        let wrong = if secret == 1 { "2" } else { "1" };
+        for _ in 0..4 {
+            loser.ask(wrong);
+        }
+        loser.send(wrong);
+        loser.receive();
+        assert_eq!(loser.receive(), "LOST");
+        assert_eq!(loser.ask(&secret.to_string()), "ERROR out of attempts");
+        assert_eq!(loser.ask(&secret.to_string()), "ERROR out of attempts");
+
+        assert_eq!(winner.ask(&secret.to_string()), "CORRECT");
+        assert_eq!(server.join().unwrap().unwrap(), "player2");
+    }
+}
@@ -0,0 +1,241 @@
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+
+use crate::game::{Game, Rules, State};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Strategy {
+    // Always guesses the middle of what's left.
+    BinarySearch,
+    // Guesses anywhere in what's left, for comparison.
+    Random,
+}
+
+impl Strategy {
+    pub const ALL: [Strategy; 2] = [Strategy::BinarySearch, Strategy::Random];
+}
+
+impl FromStr for Strategy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "binary" => Ok(Strategy::BinarySearch),
+            "random" => Ok(Strategy::Random),
+            _ => Err(format!(
+                "unknown strategy '{s}' (expected binary or random)"
+            )),
+        }
+    }
+}
+
+impl fmt::Display for Strategy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Strategy::BinarySearch => "binary",
+            Strategy::Random => "random",
+        })
+    }
+}
+
+// Guesses a secret number, keeping track of the range it can still be in.
+#[derive(Debug, Clone)]
+pub struct Solver {
+    strategy: Strategy,
+    // Wide enough that narrowing past either end of an `i64` range can't
+    // overflow.
+    low: i128,
+    high: i128,
+}
+
+impl Solver {
+    pub fn new(strategy: Strategy, range: RangeInclusive<i64>) -> Solver {
+        Solver {
+            strategy,
+            low: i128::from(*range.start()),
+            high: i128::from(*range.end()),
+        }
+    }
+
+    // Only meaningful while the solver `is_consistent`.
+    pub fn guess(&self, rng: &mut impl Rng) -> i64 {
+        let guess = match self.strategy {
+            Strategy::BinarySearch => self.low + (self.high - self.low) / 2,
+            Strategy::Random => rng.gen_range(self.low..=self.high),
+        };
+        guess as i64
+    }
+
+    // Whether some number still fits everything learned so far; answers that
+    // contradict each other leave nothing.
+    pub fn is_consistent(&self) -> bool {
+        self.low <= self.high
+    }
+
+    // Narrows the range after learning how `guess` compares with the secret.
+    pub fn learn(&mut self, guess: i64, ordering: Ordering) {
+        let guess = i128::from(guess);
+        match ordering {
+            Ordering::Less => self.low = guess + 1,
+            Ordering::Greater => self.high = guess - 1,
+            Ordering::Equal => (self.low, self.high) = (guess, guess),
+        }
+    }
+}
+
+// How a strategy did over many games.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Summary {
+    pub strategy: Strategy,
+    pub games: u32,
+    pub wins: u32,
+    pub average_attempts: f64,
+    pub worst_attempts: u32,
+}
+
+impl fmt::Display for Summary {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}: average {:.2} attempts, worst {}, won {}/{}",
+            self.strategy, self.average_attempts, self.worst_attempts, self.wins, self.games
+        )
+    }
+}
+
+// Plays `games` games with `strategy`. The same seed gives every strategy the
+// same secret numbers.
+pub fn simulate(strategy: Strategy, rules: &Rules, games: u32, seed: u64) -> Summary {
+    let mut secrets = StdRng::seed_from_u64(seed);
+    let mut guesses = StdRng::seed_from_u64(seed.wrapping_add(1));
+
+    let mut wins = 0;
+    let mut total = 0;
+    let mut worst = 0;
+
+    for _ in 0..games {
+        let mut game = Game::new(rules.clone(), &mut secrets);
+        let mut solver = Solver::new(strategy, game.range());
+
+        while !game.is_finished() {
+            let guess = solver.guess(&mut guesses);
+            if let Some(ordering) = game.guess(guess).ordering() {
+                solver.learn(guess, ordering);
+            }
+        }
+
+        if game.state() == State::Won {
+            wins += 1;
+        }
+        total += game.attempts();
+        worst = worst.max(game.attempts());
+    }
+
+    Summary {
+        strategy,
+        games,
+        wins,
+        average_attempts: if games == 0 {
+            0.0
+        } else {
+            f64::from(total) / f64::from(games)
+        },
+        worst_attempts: worst,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::Difficulty;
+
+    fn solve(strategy: Strategy, secret: i64) -> Game {
+        let mut game = Game::with_secret(Rules::new(Difficulty::Hard), secret);
+        let mut solver = Solver::new(strategy, game.range());
+        let mut rng = StdRng::seed_from_u64(0);
+
+        while !game.is_finished() {
+            let guess = solver.guess(&mut rng);
+            let ordering = game.guess(guess).ordering().unwrap();
+            solver.learn(guess, ordering);
+        }
+        game
+    }
+
+    #[test]
+    fn binary_search_finds_every_secret_in_ten_guesses() {
+        for secret in Difficulty::Hard.range() {
+            let game = solve(Strategy::BinarySearch, secret);
+            assert_eq!(game.state(), State::Won);
+            assert!(game.attempts() <= 10, "{secret} took {}", game.attempts());
+        }
+    }
+
+    #[test]
+    fn random_guesses_stay_in_the_remaining_range() {
+        let mut solver = Solver::new(Strategy::Random, 1..=100);
+        solver.learn(40, Ordering::Less);
+        solver.learn(60, Ordering::Greater);
+        let mut rng = StdRng::seed_from_u64(3);
+
+        for _ in 0..100 {
+            assert!((41..60).contains(&solver.guess(&mut rng)));
+        }
+    }
+
+    #[test]
+    fn contradictions_leave_no_candidates() {
+        let mut solver = Solver::new(Strategy::BinarySearch, 1..=100);
+        solver.learn(50, Ordering::Less);
+        solver.learn(52, Ordering::Greater);
+        assert!(solver.is_consistent());
+        assert_eq!(solver.guess(&mut StdRng::seed_from_u64(0)), 51);
+
+        solver.learn(51, Ordering::Greater);
+        assert!(!solver.is_consistent());
+    }
+
+    #[test]
+    fn binary_search_covers_the_whole_i64_range() {
+        let mut solver = Solver::new(Strategy::BinarySearch, i64::MIN..=i64::MAX);
+        let mut rng = StdRng::seed_from_u64(0);
+        let secret = -123_456_789_012;
+
+        for _ in 0..64 {
+            let guess = solver.guess(&mut rng);
+            if guess == secret {
+                return;
+            }
+            solver.learn(guess, guess.cmp(&secret));
+        }
+        panic!("took more than 64 guesses");
+    }
+
+    #[test]
+    fn simulations_are_reproducible() {
+        let first = simulate(Strategy::Random, &Rules::new(Difficulty::Medium), 200, 5);
+        let second = simulate(Strategy::Random, &Rules::new(Difficulty::Medium), 200, 5);
+
+        assert_eq!(first, second);
+        assert_eq!(first.games, 200);
+    }
+
+    #[test]
+    fn binary_search_beats_random_guessing() {
+        let binary = simulate(
+            Strategy::BinarySearch,
+            &Rules::new(Difficulty::Hard),
+            500,
+            1,
+        );
+        let random = simulate(Strategy::Random, &Rules::new(Difficulty::Hard), 500, 1);
+
+        assert_eq!(binary.wins, 500);
+        assert!(binary.worst_attempts <= 10);
+        assert!(binary.average_attempts < random.average_attempts);
+    }
+}
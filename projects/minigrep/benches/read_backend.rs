@@ -0,0 +1,66 @@
+// Compares the buffered and memory-mapped readers on files of a few sizes.
+// Run with `cargo bench`.
+
+use std::env;
+use std::fs;
+use std::hint::black_box;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use minigrep::matcher::Literal;
+use minigrep::reader::{self, Backend};
+use minigrep::records::Records;
+use minigrep::search;
+
+const RUNS: u32 = 20;
+const SIZES: &[usize] = &[64 * 1024, 1024 * 1024, 16 * 1024 * 1024, 128 * 1024 * 1024];
+
+fn fixture(size: usize) -> PathBuf {
+    let poem = include_str!("../poem.txt");
+    let mut contents = String::with_capacity(size + poem.len());
+    while contents.len() < size {
+        contents.push_str(poem);
+    }
+
+    let path = env::temp_dir().join(format!("minigrep-bench-{}-{size}.txt", std::process::id()));
+    fs::write(&path, contents).expect("failed to write benchmark fixture");
+    path
+}
+
+fn bench(path: &str, backend: Backend, matcher: &Literal) -> Duration {
+    let start = Instant::now();
+
+    for _ in 0..RUNS {
+        let bytes = reader::read(path, backend).unwrap();
+        let contents = String::from_utf8_lossy(&bytes);
+        black_box(search(matcher, &Records::Lines, &contents).len());
+    }
+
+    start.elapsed() / RUNS
+}
+
+fn main() {
+    let matcher = Literal::new("somebody");
+
+    println!("{:>12}  {:>12}  {:>12}", "size", "buffered", "mmap");
+
+    for &size in SIZES {
+        let path = fixture(size);
+        let name = path.to_str().unwrap();
+
+        // Warm the page cache so both backends start from the same place.
+        bench(name, Backend::Buffered, &matcher);
+
+        let buffered = bench(name, Backend::Buffered, &matcher);
+        let mapped = bench(name, Backend::Mmap, &matcher);
+
+        println!(
+            "{:>10}KB  {:>10.3}ms  {:>10.3}ms",
+            size / 1024,
+            buffered.as_secs_f64() * 1000.0,
+            mapped.as_secs_f64() * 1000.0
+        );
+
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,61 @@
+use std::fmt::Write;
+
+use crate::color::{BOLD, CYAN, END};
+
+// Like grep, only the start of a file is inspected when looking for NUL bytes.
+const SNIFF_LEN: usize = 8 * 1024;
+
+// Bytes of context shown on each side of a match, and bytes per hexdump row.
+const CONTEXT: usize = 16;
+const ROW: usize = 16;
+
+pub fn is_binary(bytes: &[u8]) -> bool {
+    bytes[..bytes.len().min(SNIFF_LEN)].contains(&0)
+}
+
+// Renders the rows around `span` in the classic `hexdump -C` layout, with the
+// matched bytes highlighted when colour is enabled.
+pub fn hexdump(bytes: &[u8], span: (usize, usize), color: bool) -> String {
+    let (start, end) = span;
+    let first = start.saturating_sub(CONTEXT) / ROW * ROW;
+    let last = (end + CONTEXT).min(bytes.len());
+
+    let mut out = String::new();
+
+    for row_start in (first..last).step_by(ROW) {
+        let row = &bytes[row_start..(row_start + ROW).min(bytes.len())];
+        let mut hex = String::new();
+        let mut ascii = String::new();
+
+        for i in 0..ROW {
+            if i == ROW / 2 {
+                hex.push(' ');
+            }
+
+            let Some(&byte) = row.get(i) else {
+                hex.push_str("   ");
+                continue;
+            };
+
+            let offset = row_start + i;
+            let matched = color && offset >= start && offset < end;
+            let shown = if byte.is_ascii_graphic() || byte == b' ' {
+                byte as char
+            } else {
+                '.'
+            };
+
+            if matched {
+                write!(hex, "{BOLD}{CYAN}{byte:02x}{END} ").unwrap();
+                write!(ascii, "{BOLD}{CYAN}{shown}{END}").unwrap();
+            } else {
+                write!(hex, "{byte:02x} ").unwrap();
+                ascii.push(shown);
+            }
+        }
+
+        writeln!(out, "{row_start:08x}  {hex} |{ascii}|").unwrap();
+    }
+
+    out
+}
@@ -0,0 +1,322 @@
+// Every option minigrep understands. The argument parser, `--help`, the shell
+// completions and the man page are all derived from these tables, so adding a
+// flag here is enough to document it everywhere.
+
+pub const NAME: &str = env!("CARGO_PKG_NAME");
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+pub const ABOUT: &str = "search files for lines matching a query";
+
+pub enum Value {
+    // A plain switch, e.g. `--regex`.
+    Switch,
+    // A value that must be given, as `--flag VALUE` or `--flag=VALUE`.
+    Required {
+        name: &'static str,
+        choices: &'static [&'static str],
+    },
+    // A value that can only be given inline, as `--flag=VALUE`.
+    Optional {
+        name: &'static str,
+        choices: &'static [&'static str],
+    },
+}
+
+pub struct Flag {
+    pub long: &'static str,
+    pub short: Option<char>,
+    pub value: Value,
+    pub help: &'static str,
+}
+
+impl Flag {
+    pub fn choices(&self) -> &'static [&'static str] {
+        match self.value {
+            Value::Switch => &[],
+            Value::Required { choices, .. } | Value::Optional { choices, .. } => choices,
+        }
+    }
+
+    // How the flag is written in usage text, e.g. `--stats[=FORMAT]`.
+    pub fn signature(&self) -> String {
+        match self.value {
+            Value::Switch => format!("--{}", self.long),
+            Value::Required { name, .. } => format!("--{} {name}", self.long),
+            Value::Optional { name, .. } => format!("--{}[={name}]", self.long),
+        }
+    }
+}
+
+pub struct Positional {
+    pub name: &'static str,
+    pub help: &'static str,
+}
+
+pub const FLAGS: &[Flag] = &[
+    Flag {
+        long: "ignore-case",
+        short: Some('i'),
+        value: Value::Switch,
+        help: "Match without regard to case (also enabled by IGNORE_CASE)",
+    },
+    Flag {
+        long: "regex",
+        short: Some('E'),
+        value: Value::Switch,
+        help: "Treat QUERY as a regular expression",
+    },
+    Flag {
+        long: "fuzzy",
+        short: None,
+        value: Value::Required {
+            name: "N",
+            choices: &[],
+        },
+        help: "Also match text within N inserted, deleted or changed characters of QUERY",
+    },
+    Flag {
+        long: "bool",
+        short: None,
+        value: Value::Switch,
+        help: "Treat QUERY as terms combined with AND, OR, NOT and parentheses",
+    },
+    Flag {
+        long: "paragraph",
+        short: Some('p'),
+        value: Value::Switch,
+        help: "Match and print paragraphs separated by blank lines instead of lines",
+    },
+    Flag {
+        long: "record-separator",
+        short: None,
+        value: Value::Required {
+            name: "REGEX",
+            choices: &[],
+        },
+        help: "Match and print records separated by REGEX instead of lines",
+    },
+    Flag {
+        long: "max-filesize",
+        short: None,
+        value: Value::Required {
+            name: "SIZE",
+            choices: &[],
+        },
+        help: "Skip files in directories larger than SIZE, e.g. 512K or 10M",
+    },
+    Flag {
+        long: "newer-than",
+        short: None,
+        value: Value::Required {
+            name: "AGE",
+            choices: &[],
+        },
+        help: "Skip files in directories not modified within AGE, e.g. 12h or 2d",
+    },
+    Flag {
+        long: "type",
+        short: Some('t'),
+        value: Value::Required {
+            name: "TYPE",
+            choices: &[],
+        },
+        help: "Only search files in directories of TYPE: log, rust, text, or one from \
+               ~/.config/minigrep/types (repeatable)",
+    },
+    Flag {
+        long: "quiet",
+        short: Some('q'),
+        value: Value::Switch,
+        help: "Print nothing; stop at the first match and report it only through the exit status",
+    },
+    Flag {
+        long: "stats",
+        short: None,
+        value: Value::Optional {
+            name: "FORMAT",
+            choices: &["human", "json"],
+        },
+        help: "Report match statistics instead of printing lines",
+    },
+    Flag {
+        long: "max-count",
+        short: Some('m'),
+        value: Value::Required {
+            name: "N",
+            choices: &[],
+        },
+        help: "Stop reading a file after N matching lines",
+    },
+    Flag {
+        long: "max-total",
+        short: None,
+        value: Value::Required {
+            name: "N",
+            choices: &[],
+        },
+        help: "Stop searching after N matching lines in total",
+    },
+    Flag {
+        long: "sort",
+        short: None,
+        value: Value::Required {
+            name: "ORDER",
+            choices: &["path", "line", "none"],
+        },
+        help: "Print matches ordered by file path, by line number, or as found",
+    },
+    Flag {
+        long: "unique",
+        short: None,
+        value: Value::Switch,
+        help: "Print each distinct matching line once, with how often it matched",
+    },
+    Flag {
+        long: "column",
+        short: None,
+        value: Value::Switch,
+        help: "Print the column of the first match, counted in user-perceived characters",
+    },
+    Flag {
+        long: "binary-hexdump",
+        short: None,
+        value: Value::Switch,
+        help: "Show each match in a binary file as a hexdump with surrounding bytes",
+    },
+    Flag {
+        long: "mmap",
+        short: None,
+        value: Value::Switch,
+        help: "Always memory-map files instead of reading them into memory",
+    },
+    Flag {
+        long: "no-mmap",
+        short: None,
+        value: Value::Switch,
+        help: "Never memory-map files (by default files of 1MiB or more are mapped)",
+    },
+    Flag {
+        long: "watch",
+        short: None,
+        value: Value::Switch,
+        help: "Search again, clearing the screen, whenever a searched file changes",
+    },
+    Flag {
+        long: "compare",
+        short: None,
+        value: Value::Switch,
+        help: "Take OLD NEW QUERY and report matches only in lines added (+) or removed (-)",
+    },
+    Flag {
+        long: "generate",
+        short: None,
+        value: Value::Required {
+            name: "KIND",
+            choices: &["completions", "man"],
+        },
+        help: "Print shell completions (followed by bash, zsh or fish) or a man page",
+    },
+    Flag {
+        long: "help",
+        short: Some('h'),
+        value: Value::Switch,
+        help: "Print help",
+    },
+];
+
+pub const POSITIONALS: &[Positional] = &[
+    Positional {
+        name: "QUERY",
+        help: "The text to search for",
+    },
+    Positional {
+        name: "PATH...",
+        help: "Files or directories to search; .tar archives are searched inside",
+    },
+];
+
+pub const USAGE: &[&str] = &[
+    "[OPTIONS] QUERY PATH...",
+    "[OPTIONS] --compare OLD NEW QUERY",
+    "--generate completions bash|zsh|fish",
+    "--generate man",
+];
+
+pub const SHELLS: &[&str] = &["bash", "zsh", "fish"];
+
+pub struct Parsed {
+    pub flags: Vec<(&'static Flag, Option<String>)>,
+    pub positional: Vec<String>,
+}
+
+pub fn parse(mut args: impl Iterator<Item = String>) -> Result<Parsed, String> {
+    let mut flags = Vec::new();
+    let mut positional = Vec::new();
+
+    while let Some(arg) = args.next() {
+        if arg == "--" {
+            positional.extend(args.by_ref());
+        } else if let Some(long) = arg.strip_prefix("--") {
+            let (name, inline) = match long.split_once('=') {
+                Some((name, value)) => (name, Some(value.to_string())),
+                None => (long, None),
+            };
+
+            let flag = FLAGS
+                .iter()
+                .find(|flag| flag.long == name)
+                .ok_or_else(|| format!("unknown option '--{name}'"))?;
+
+            let value = match (&flag.value, inline) {
+                (Value::Switch, Some(_)) => {
+                    return Err(format!("option '--{name}' doesn't take a value"));
+                }
+                (Value::Required { .. }, None) => Some(
+                    args.next()
+                        .ok_or_else(|| format!("option '--{name}' needs a value"))?,
+                ),
+                (_, inline) => inline,
+            };
+
+            flags.push((flag, check_choice(flag, value)?));
+        } else if arg.starts_with('-') && arg.len() > 1 {
+            // Short options can be bundled, as in `-iE`.
+            for (i, c) in arg.char_indices().skip(1) {
+                let flag = FLAGS
+                    .iter()
+                    .find(|flag| flag.short == Some(c))
+                    .ok_or_else(|| format!("unknown option '-{c}'"))?;
+
+                if let Value::Required { .. } = flag.value {
+                    let rest = &arg[i + c.len_utf8()..];
+                    let value = if rest.is_empty() {
+                        args.next()
+                            .ok_or_else(|| format!("option '-{c}' needs a value"))?
+                    } else {
+                        rest.to_string()
+                    };
+                    flags.push((flag, check_choice(flag, Some(value))?));
+                    break;
+                }
+
+                flags.push((flag, None));
+            }
+        } else {
+            positional.push(arg);
+        }
+    }
+
+    Ok(Parsed { flags, positional })
+}
+
+fn check_choice(flag: &Flag, value: Option<String>) -> Result<Option<String>, String> {
+    let choices = flag.choices();
+
+    match value {
+        Some(value) if !choices.is_empty() && !choices.contains(&value.as_str()) => Err(format!(
+            "invalid value '{value}' for '--{}' (expected {})",
+            flag.long,
+            choices.join(", ")
+        )),
+        value => Ok(value),
+    }
+}
@@ -0,0 +1,12 @@
+// ANSI escape sequences used to colour the terminal output.
+
+pub const REVERSED: &str = "\u{001b}[7m";
+pub const RED: &str = "\u{001b}[31m";
+pub const GREEN: &str = "\u{001b}[32m";
+pub const YELLOW: &str = "\u{001b}[33m";
+pub const BLUE: &str = "\u{001b}[34m";
+pub const MAGENTA: &str = "\u{001b}[35m";
+pub const CYAN: &str = "\u{001b}[36m";
+pub const WHITE: &str = "\u{001b}[37m";
+pub const BOLD: &str = "\u{001b}[1m";
+pub const END: &str = "\u{001b}[0m";
@@ -0,0 +1,329 @@
+// Line diffs using Myers' O(ND) algorithm, as described in "An O(ND)
+// Difference Algorithm and Its Variations" (1986), in its linear-space form:
+// rather than keeping every frontier to walk the path back, each step finds
+// the middle snake of the shortest path and recurses on either side of it.
+
+use std::ops::Range;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Edit {
+    // The line is in both versions, at these indices.
+    Keep { old: usize, new: usize },
+    // The line at this index of the old version was removed.
+    Remove(usize),
+    // The line at this index of the new version was added.
+    Add(usize),
+}
+
+// Returns the shortest edit script turning `old` into `new`.
+pub fn diff<T: PartialEq>(old: &[T], new: &[T]) -> Vec<Edit> {
+    let mut frontiers = Frontiers::new(old.len() + new.len());
+    let mut edits = Vec::new();
+    diff_ranges(
+        old,
+        0..old.len(),
+        new,
+        0..new.len(),
+        &mut frontiers,
+        &mut edits,
+    );
+    edits
+}
+
+// The furthest x reached on each diagonal k = x - y, searching forwards and
+// backwards. Diagonals can be negative, so they are shifted by `offset`.
+struct Frontiers {
+    forward: Vec<usize>,
+    backward: Vec<usize>,
+    offset: isize,
+}
+
+impl Frontiers {
+    fn new(len: usize) -> Frontiers {
+        let offset = len as isize / 2 + 2;
+        Frontiers {
+            forward: vec![0; 2 * offset as usize + 1],
+            backward: vec![0; 2 * offset as usize + 1],
+            offset,
+        }
+    }
+
+    fn index(&self, k: isize) -> usize {
+        (k + self.offset) as usize
+    }
+}
+
+fn diff_ranges<T: PartialEq>(
+    old: &[T],
+    mut old_range: Range<usize>,
+    new: &[T],
+    mut new_range: Range<usize>,
+    frontiers: &mut Frontiers,
+    edits: &mut Vec<Edit>,
+) {
+    // Lines the two ends have in common are kept as they are.
+    while !old_range.is_empty()
+        && !new_range.is_empty()
+        && old[old_range.start] == new[new_range.start]
+    {
+        edits.push(Edit::Keep {
+            old: old_range.start,
+            new: new_range.start,
+        });
+        old_range.start += 1;
+        new_range.start += 1;
+    }
+
+    let mut suffix = 0;
+    while !old_range.is_empty()
+        && !new_range.is_empty()
+        && old[old_range.end - 1] == new[new_range.end - 1]
+    {
+        old_range.end -= 1;
+        new_range.end -= 1;
+        suffix += 1;
+    }
+
+    if old_range.is_empty() {
+        edits.extend(new_range.clone().map(Edit::Add));
+    } else if new_range.is_empty() {
+        edits.extend(old_range.clone().map(Edit::Remove));
+    } else {
+        let (x, y) = middle_snake(old, old_range.clone(), new, new_range.clone(), frontiers);
+        diff_ranges(
+            old,
+            old_range.start..x,
+            new,
+            new_range.start..y,
+            frontiers,
+            edits,
+        );
+        diff_ranges(
+            old,
+            x..old_range.end,
+            new,
+            y..new_range.end,
+            frontiers,
+            edits,
+        );
+    }
+
+    edits.extend((0..suffix).map(|i| Edit::Keep {
+        old: old_range.end + i,
+        new: new_range.end + i,
+    }));
+}
+
+// Finds a point on a shortest path through the ranges, roughly half way
+// along it, by searching from both ends until the searches overlap. The
+// ranges must both be non-empty and differ at both ends.
+fn middle_snake<T: PartialEq>(
+    old: &[T],
+    old_range: Range<usize>,
+    new: &[T],
+    new_range: Range<usize>,
+    frontiers: &mut Frontiers,
+) -> (usize, usize) {
+    let n = old_range.len();
+    let m = new_range.len();
+    let delta = n as isize - m as isize;
+    let odd = delta % 2 != 0;
+    let max = (n + m).div_ceil(2) as isize;
+
+    let at_start = frontiers.index(1);
+    frontiers.forward[at_start] = 0;
+    frontiers.backward[at_start] = 0;
+
+    for d in 0..=max {
+        for k in (-d..=d).rev().step_by(2) {
+            let (down, right) = (frontiers.index(k + 1), frontiers.index(k - 1));
+            let mut x = if k == -d || (k != d && frontiers.forward[right] < frontiers.forward[down])
+            {
+                frontiers.forward[down]
+            } else {
+                frontiers.forward[right] + 1
+            };
+            let start = (x, (x as isize - k) as usize);
+            let mut y = start.1;
+
+            while x < n && y < m && old[old_range.start + x] == new[new_range.start + y] {
+                x += 1;
+                y += 1;
+            }
+            let i = frontiers.index(k);
+            frontiers.forward[i] = x;
+
+            // The backward search on this diagonal got as far as round d - 1.
+            let opposite = -(k - delta);
+            if odd && opposite.abs() < d && x + frontiers.backward[frontiers.index(opposite)] >= n {
+                return (old_range.start + start.0, new_range.start + start.1);
+            }
+        }
+
+        // Going the other way round here puts removals before additions.
+        for k in (-d..=d).step_by(2) {
+            let (down, right) = (frontiers.index(k + 1), frontiers.index(k - 1));
+            let mut x =
+                if k == -d || (k != d && frontiers.backward[right] < frontiers.backward[down]) {
+                    frontiers.backward[down]
+                } else {
+                    frontiers.backward[right] + 1
+                };
+            let mut y = (x as isize - k) as usize;
+
+            while x < n
+                && y < m
+                && old[old_range.start + n - x - 1] == new[new_range.start + m - y - 1]
+            {
+                x += 1;
+                y += 1;
+            }
+            let i = frontiers.index(k);
+            frontiers.backward[i] = x;
+
+            let opposite = -(k - delta);
+            if !odd && opposite.abs() <= d && x + frontiers.forward[frontiers.index(opposite)] >= n
+            {
+                return (old_range.start + n - x, new_range.start + m - y);
+            }
+        }
+    }
+
+    unreachable!("the searches always meet within (n + m) / 2 rounds")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Applies `edits` to `old`, checking each one against both versions.
+    fn apply<'a>(old: &[&'a str], new: &[&'a str], edits: &[Edit]) -> Vec<&'a str> {
+        let (mut next_old, mut next_new) = (0, 0);
+        let mut result = Vec::new();
+
+        for &edit in edits {
+            match edit {
+                Edit::Keep { old: o, new: n } => {
+                    assert_eq!((o, n), (next_old, next_new), "{edits:?}");
+                    assert_eq!(old[o], new[n]);
+                    result.push(old[o]);
+                    next_old += 1;
+                    next_new += 1;
+                }
+                Edit::Remove(o) => {
+                    assert_eq!(o, next_old, "{edits:?}");
+                    next_old += 1;
+                }
+                Edit::Add(n) => {
+                    assert_eq!(n, next_new, "{edits:?}");
+                    result.push(new[n]);
+                    next_new += 1;
+                }
+            }
+        }
+
+        assert_eq!((next_old, next_new), (old.len(), new.len()));
+        result
+    }
+
+    fn changes(edits: &[Edit]) -> usize {
+        edits
+            .iter()
+            .filter(|edit| !matches!(edit, Edit::Keep { .. }))
+            .count()
+    }
+
+    #[test]
+    fn both_empty() {
+        assert_eq!(diff::<&str>(&[], &[]), []);
+    }
+
+    #[test]
+    fn all_added() {
+        assert_eq!(diff(&[], &["a", "b"]), [Edit::Add(0), Edit::Add(1)]);
+    }
+
+    #[test]
+    fn all_removed() {
+        assert_eq!(diff(&["a", "b"], &[]), [Edit::Remove(0), Edit::Remove(1)]);
+    }
+
+    #[test]
+    fn change_in_the_middle() {
+        let old = ["a", "b", "c", "d"];
+        let new = ["a", "x", "c", "d"];
+
+        assert_eq!(
+            diff(&old, &new),
+            [
+                Edit::Keep { old: 0, new: 0 },
+                Edit::Remove(1),
+                Edit::Add(1),
+                Edit::Keep { old: 2, new: 2 },
+                Edit::Keep { old: 3, new: 3 },
+            ]
+        );
+    }
+
+    #[test]
+    fn kept_lines_line_up() {
+        let old: Vec<&str> = "a b c a b b a".split(' ').collect();
+        let new: Vec<&str> = "c b a b a c".split(' ').collect();
+
+        let edits = diff(&old, &new);
+
+        assert_eq!(apply(&old, &new, &edits), new);
+        // The example from Myers' paper has an edit distance of 5.
+        assert_eq!(changes(&edits), 5);
+    }
+
+    #[test]
+    fn scripts_are_shortest() {
+        // Every pair of sequences of up to four lines from a small alphabet,
+        // checked against the edit distance from a longest common subsequence.
+        let mut sequences: Vec<Vec<&str>> = vec![vec![]];
+        for len in 1..=4 {
+            for previous in sequences.clone() {
+                if previous.len() == len - 1 {
+                    for line in ["a", "b", "c"] {
+                        sequences.push([previous.as_slice(), &[line]].concat());
+                    }
+                }
+            }
+        }
+
+        for old in &sequences {
+            for new in &sequences {
+                let edits = diff(old, new);
+
+                assert_eq!(&apply(old, new, &edits), new);
+                assert_eq!(changes(&edits), old.len() + new.len() - 2 * lcs(old, new));
+            }
+        }
+    }
+
+    fn lcs(a: &[&str], b: &[&str]) -> usize {
+        let mut table = vec![vec![0; b.len() + 1]; a.len() + 1];
+        for i in 0..a.len() {
+            for j in 0..b.len() {
+                table[i + 1][j + 1] = if a[i] == b[j] {
+                    table[i][j] + 1
+                } else {
+                    table[i][j + 1].max(table[i + 1][j])
+                };
+            }
+        }
+        table[a.len()][b.len()]
+    }
+
+    #[test]
+    fn large_rewrites_are_quick() {
+        let old: Vec<String> = (0..4000).map(|i| format!("old {i}")).collect();
+        let new: Vec<String> = (0..4000).map(|i| format!("new {i}")).collect();
+
+        let edits = diff(&old, &new);
+
+        assert_eq!(edits.len(), 8000);
+        assert!(edits.iter().all(|edit| !matches!(edit, Edit::Keep { .. })));
+    }
+}
@@ -0,0 +1,141 @@
+use std::collections::BTreeMap;
+use std::env;
+use std::fs::{self, Metadata};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+// File types known without any configuration, as extensions.
+const BUILTIN_TYPES: &[(&str, &[&str])] = &[
+    ("log", &["log"]),
+    ("rust", &["rs"]),
+    ("text", &["txt", "text", "md"]),
+];
+
+// Every file type, by name: the built-in ones plus any from the types file,
+// which holds lines like `toml: toml lock`. A name that's already known gains
+// the extra extensions.
+pub fn types() -> Result<BTreeMap<String, Vec<String>>, String> {
+    let mut types: BTreeMap<String, Vec<String>> = BUILTIN_TYPES
+        .iter()
+        .map(|(name, extensions)| {
+            let extensions = extensions.iter().map(|ext| ext.to_string()).collect();
+            (name.to_string(), extensions)
+        })
+        .collect();
+
+    let Some(path) = types_file() else {
+        return Ok(types);
+    };
+    let Ok(contents) = fs::read_to_string(&path) else {
+        return Ok(types);
+    };
+
+    for (i, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let Some((name, extensions)) = line.split_once(':') else {
+            return Err(format!(
+                "{}:{}: expected 'name: extension...'",
+                path.display(),
+                i + 1
+            ));
+        };
+
+        types.entry(name.trim().to_string()).or_default().extend(
+            extensions
+                .split_whitespace()
+                .map(|ext| ext.trim_start_matches('.').to_string()),
+        );
+    }
+
+    Ok(types)
+}
+
+// `$XDG_CONFIG_HOME/minigrep/types`, or `~/.config/minigrep/types`.
+pub fn types_file() -> Option<PathBuf> {
+    let config = env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".config")))?;
+    Some(config.join("minigrep").join("types"))
+}
+
+// Parses a size such as `512`, `10K`, `10M` or `1G` (powers of 1024).
+pub fn parse_size(size: &str) -> Result<u64, String> {
+    let invalid = || format!("invalid size '{size}' (expected e.g. 512, 10K, 10M or 1G)");
+
+    let digits = size.trim_end_matches(|c: char| c.is_ascii_alphabetic());
+    let multiplier: u64 = match size[digits.len()..].to_ascii_uppercase().as_str() {
+        "" | "B" => 1,
+        "K" | "KB" => 1 << 10,
+        "M" | "MB" => 1 << 20,
+        "G" | "GB" => 1 << 30,
+        _ => return Err(invalid()),
+    };
+
+    let number: u64 = digits.parse().map_err(|_| invalid())?;
+    number.checked_mul(multiplier).ok_or_else(invalid)
+}
+
+// Parses an age such as `30s`, `15m`, `12h`, `2d` or `1w`.
+pub fn parse_age(age: &str) -> Result<Duration, String> {
+    let invalid = || format!("invalid age '{age}' (expected e.g. 30s, 15m, 12h, 2d or 1w)");
+
+    let digits = age.trim_end_matches(|c: char| c.is_ascii_alphabetic());
+    let seconds: u64 = match &age[digits.len()..] {
+        "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => 24 * 60 * 60,
+        "w" => 7 * 24 * 60 * 60,
+        _ => return Err(invalid()),
+    };
+
+    let number: u64 = digits.parse().map_err(|_| invalid())?;
+    number
+        .checked_mul(seconds)
+        .map(Duration::from_secs)
+        .ok_or_else(invalid)
+}
+
+// Which of the files found inside directories are searched. Files named
+// directly on the command line are always searched.
+#[derive(Debug, Clone, Default)]
+pub struct FileFilter {
+    pub max_filesize: Option<u64>,
+    pub newer_than: Option<Duration>,
+    // Allowed extensions; empty allows every file.
+    pub extensions: Vec<String>,
+}
+
+impl FileFilter {
+    pub fn accepts(&self, path: &Path, metadata: &Metadata) -> bool {
+        if self.max_filesize.is_some_and(|max| metadata.len() > max) {
+            return false;
+        }
+
+        if let Some(age) = self.newer_than {
+            let cutoff = SystemTime::now()
+                .checked_sub(age)
+                .unwrap_or(SystemTime::UNIX_EPOCH);
+            if metadata
+                .modified()
+                .map_or(true, |modified| modified < cutoff)
+            {
+                return false;
+            }
+        }
+
+        if !self.extensions.is_empty() {
+            let extension = path.extension().and_then(|ext| ext.to_str());
+            if !extension.is_some_and(|ext| self.extensions.iter().any(|allowed| allowed == ext)) {
+                return false;
+            }
+        }
+
+        true
+    }
+}
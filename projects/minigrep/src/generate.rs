@@ -0,0 +1,340 @@
+use std::fmt::Write;
+use std::str::FromStr;
+
+use crate::cli::{Flag, Value, ABOUT, FLAGS, NAME, POSITIONALS, SHELLS, USAGE, VERSION};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+}
+
+impl FromStr for Shell {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "bash" => Ok(Shell::Bash),
+            "zsh" => Ok(Shell::Zsh),
+            "fish" => Ok(Shell::Fish),
+            _ => Err(format!(
+                "unknown shell '{s}' (expected {})",
+                SHELLS.join(", ")
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Generate {
+    Completions(Shell),
+    Man,
+}
+
+impl Generate {
+    pub fn render(self) -> String {
+        match self {
+            Generate::Completions(Shell::Bash) => bash(),
+            Generate::Completions(Shell::Zsh) => zsh(),
+            Generate::Completions(Shell::Fish) => fish(),
+            Generate::Man => man(),
+        }
+    }
+}
+
+fn flag_names(flag: &Flag) -> String {
+    match flag.short {
+        Some(short) => format!("-{short}, {}", flag.signature()),
+        None => format!("    {}", flag.signature()),
+    }
+}
+
+pub fn help() -> String {
+    let mut out = String::new();
+    writeln!(out, "{NAME} {VERSION}: {ABOUT}\n").unwrap();
+
+    for (i, usage) in USAGE.iter().enumerate() {
+        let label = if i == 0 { "Usage:" } else { "" };
+        writeln!(out, "{label:<6} {NAME} {usage}").unwrap();
+    }
+
+    let width = FLAGS
+        .iter()
+        .map(|flag| flag_names(flag).len())
+        .chain(POSITIONALS.iter().map(|p| p.name.len()))
+        .max()
+        .unwrap_or(0);
+
+    writeln!(out, "\nArguments:").unwrap();
+    for positional in POSITIONALS {
+        writeln!(out, "  {:<width$}  {}", positional.name, positional.help).unwrap();
+    }
+
+    writeln!(out, "\nOptions:").unwrap();
+    for flag in FLAGS {
+        writeln!(out, "  {:<width$}  {}", flag_names(flag), flag.help).unwrap();
+    }
+
+    out
+}
+
+fn bash() -> String {
+    let mut words = Vec::new();
+    let mut cases = String::new();
+
+    for flag in FLAGS {
+        if let Some(short) = flag.short {
+            words.push(format!("-{short}"));
+        }
+        words.push(format!("--{}", flag.long));
+
+        match flag.value {
+            Value::Switch => {}
+            Value::Optional { choices, .. } => {
+                words.extend(choices.iter().map(|c| format!("--{}={c}", flag.long)));
+            }
+            Value::Required { choices, .. } => {
+                let mut patterns = vec![format!("--{}", flag.long)];
+                if let Some(short) = flag.short {
+                    patterns.push(format!("-{short}"));
+                }
+                let reply = if choices.is_empty() {
+                    // Free-form values such as numbers: nothing to offer.
+                    String::new()
+                } else {
+                    format!("$(compgen -W \"{}\" -- \"$cur\")", choices.join(" "))
+                };
+                writeln!(
+                    cases,
+                    "        {})\n            COMPREPLY=({reply})\n            return 0\n            ;;",
+                    patterns.join("|")
+                )
+                .unwrap();
+            }
+        }
+    }
+
+    format!(
+        r#"_{NAME}() {{
+    local cur prev
+    COMPREPLY=()
+    cur="${{COMP_WORDS[COMP_CWORD]}}"
+    prev="${{COMP_WORDS[COMP_CWORD-1]}}"
+
+    case "$prev" in
+{cases}        completions)
+            COMPREPLY=($(compgen -W "{shells}" -- "$cur"))
+            return 0
+            ;;
+    esac
+
+    if [[ "$cur" == -* ]]; then
+        COMPREPLY=($(compgen -W "{words}" -- "$cur"))
+        return 0
+    fi
+
+    COMPREPLY=($(compgen -f -- "$cur"))
+}}
+
+complete -o filenames -F _{NAME} {NAME}
+"#,
+        shells = SHELLS.join(" "),
+        words = words.join(" "),
+    )
+}
+
+fn zsh_escape(s: &str) -> String {
+    s.replace('\\', "\\\\")
+        .replace('\'', "'\\''")
+        .replace('[', "\\[")
+        .replace(']', "\\]")
+        .replace(':', "\\:")
+}
+
+fn zsh() -> String {
+    let mut specs = Vec::new();
+
+    for flag in FLAGS {
+        let help = zsh_escape(flag.help);
+        let action = match flag.value {
+            Value::Switch => String::new(),
+            Value::Required { name, choices } | Value::Optional { name, choices } => {
+                let optional = matches!(flag.value, Value::Optional { .. });
+                let values = if choices.is_empty() {
+                    " ".to_string()
+                } else {
+                    format!("({})", choices.join(" "))
+                };
+                format!("{}{name}:{values}", if optional { "::" } else { ":" })
+            }
+        };
+        let long = match flag.value {
+            Value::Optional { .. } => format!("--{}=-", flag.long),
+            Value::Required { .. } => format!("--{}=", flag.long),
+            Value::Switch => format!("--{}", flag.long),
+        };
+
+        match flag.short {
+            Some(short) => specs.push(format!(
+                "'(-{short} --{long_name})'{{-{short},{long}}}'[{help}]{action}'",
+                long_name = flag.long
+            )),
+            None => specs.push(format!("'{long}[{help}]{action}'")),
+        }
+    }
+
+    specs.push("'1:QUERY: '".to_string());
+    specs.push("'*:PATH:_files'".to_string());
+
+    format!(
+        "#compdef {NAME}\n\n_{NAME}() {{\n    _arguments -s \\\n        {}\n}}\n\n_{NAME} \"$@\"\n",
+        specs.join(" \\\n        ")
+    )
+}
+
+fn fish_escape(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('\'', "\\'")
+}
+
+fn fish() -> String {
+    let mut out = String::new();
+
+    for flag in FLAGS {
+        write!(out, "complete -c {NAME}").unwrap();
+        if let Some(short) = flag.short {
+            write!(out, " -s {short}").unwrap();
+        }
+        write!(out, " -l {}", flag.long).unwrap();
+
+        // Fish can't complete inline `--flag=VALUE` values, so only required
+        // values offer their choices.
+        if let Value::Required { choices, .. } = flag.value {
+            out.push_str(" -r");
+            if choices.is_empty() {
+                out.push_str(" -f");
+            } else {
+                write!(out, " -f -a '{}'", choices.join(" ")).unwrap();
+            }
+        }
+        writeln!(out, " -d '{}'", fish_escape(flag.help)).unwrap();
+    }
+
+    writeln!(
+        out,
+        "complete -c {NAME} -n '__fish_seen_argument -l generate; and __fish_seen_subcommand_from completions' -f -a '{}'",
+        SHELLS.join(" ")
+    )
+    .unwrap();
+
+    out
+}
+
+fn roff_escape(s: &str) -> String {
+    let escaped = s.replace('\\', "\\e").replace('-', "\\-");
+    // A leading dot or quote would be read as a request.
+    if escaped.starts_with('.') || escaped.starts_with('\'') {
+        format!("\\&{escaped}")
+    } else {
+        escaped
+    }
+}
+
+fn man() -> String {
+    let mut out = String::new();
+    writeln!(
+        out,
+        ".TH {} 1 \"\" \"{NAME} {VERSION}\" \"User Commands\"",
+        NAME.to_uppercase()
+    )
+    .unwrap();
+    writeln!(out, ".SH NAME\n{NAME} \\- {}", roff_escape(ABOUT)).unwrap();
+
+    writeln!(out, ".SH SYNOPSIS").unwrap();
+    for (i, usage) in USAGE.iter().enumerate() {
+        if i > 0 {
+            writeln!(out, ".br").unwrap();
+        }
+        writeln!(out, ".B {NAME}\n{}", roff_escape(usage)).unwrap();
+    }
+
+    writeln!(
+        out,
+        ".SH DESCRIPTION\n\
+         {NAME} searches each PATH for lines containing QUERY and prints them, \
+         prefixed by their line number and, when several files are searched, by the file name. \
+         Directories are searched recursively, skipping hidden entries. \
+         Files containing NUL bytes are treated as binary: only whether they match is reported. \
+         Files ending in .tar are searched entry by entry, reported as archive.tar!inner/path."
+    )
+    .unwrap();
+
+    writeln!(
+        out,
+        ".PP\n\
+         With \\fB\\-\\-bool\\fR, QUERY combines terms with \\fBAND\\fR, \\fBOR\\fR, \\fBNOT\\fR \
+         and parentheses, for example \\fInobody AND (frog OR bog) AND NOT dreary\\fR. \
+         Terms containing spaces or operators can be written in double quotes."
+    )
+    .unwrap();
+
+    writeln!(out, ".SH ARGUMENTS").unwrap();
+    for positional in POSITIONALS {
+        writeln!(
+            out,
+            ".TP\n\\fI{}\\fR\n{}",
+            roff_escape(positional.name),
+            roff_escape(positional.help)
+        )
+        .unwrap();
+    }
+
+    writeln!(out, ".SH OPTIONS").unwrap();
+    for flag in FLAGS {
+        let long = match flag.value {
+            Value::Switch => format!("\\fB\\-\\-{}\\fR", roff_escape(flag.long)),
+            Value::Required { name, .. } => {
+                format!("\\fB\\-\\-{}\\fR \\fI{name}\\fR", roff_escape(flag.long))
+            }
+            Value::Optional { name, .. } => {
+                format!("\\fB\\-\\-{}\\fR[=\\fI{name}\\fR]", roff_escape(flag.long))
+            }
+        };
+        let names = match flag.short {
+            Some(short) => format!("\\fB\\-{short}\\fR, {long}"),
+            None => long,
+        };
+
+        write!(out, ".TP\n{names}\n{}", roff_escape(flag.help)).unwrap();
+        let choices = flag.choices();
+        if !choices.is_empty() {
+            write!(out, " (one of: {})", choices.join(", ")).unwrap();
+        }
+        writeln!(out).unwrap();
+    }
+
+    writeln!(
+        out,
+        ".SH EXIT STATUS\n\
+         0 if anything matched, 1 if nothing did, and 2 if an error occurred. \
+         A file that can't be read is reported and the others are still searched; \
+         with \\fB\\-q\\fR a match elsewhere still gives 0."
+    )
+    .unwrap();
+
+    writeln!(
+        out,
+        ".SH ENVIRONMENT\n.TP\n.B IGNORE_CASE\nWhen set, searches ignore case as if \\fB\\-i\\fR was given."
+    )
+    .unwrap();
+
+    writeln!(
+        out,
+        ".SH FILES\n.TP\n.I ~/.config/minigrep/types\n\
+         Extra file types for \\fB\\-\\-type\\fR, one per line as \\fIname: extension...\\fR. \
+         Read from \\fI$XDG_CONFIG_HOME/minigrep/types\\fR when XDG_CONFIG_HOME is set."
+    )
+    .unwrap();
+
+    out
+}
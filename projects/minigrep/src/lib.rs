@@ -0,0 +1,418 @@
+use std::env;
+use std::error::Error;
+use std::io::{self, IsTerminal, Write};
+use std::path::Path;
+use std::str::FromStr;
+use std::time::Instant;
+
+pub mod binary;
+pub mod cli;
+pub mod color;
+pub mod diff;
+pub mod filter;
+pub mod generate;
+pub mod matcher;
+pub mod printer;
+pub mod query;
+pub mod reader;
+pub mod records;
+pub mod searcher;
+pub mod stats;
+pub mod tar;
+pub mod unicode;
+pub mod walk;
+pub mod watch;
+
+use color::{CYAN, END, GREEN, RED};
+use diff::Edit;
+use filter::FileFilter;
+use generate::Generate;
+use matcher::MatcherOptions;
+use printer::highlight;
+use reader::Backend;
+use records::Records;
+use stats::{Stats, StatsFormat};
+use watch::{Watcher, CLEAR_SCREEN};
+
+pub use matcher::Matcher;
+pub use printer::Printer;
+pub use searcher::{match_line, search, Discard, Match, Searcher, Sink};
+
+// How a run went, which decides the exit status.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    Matched,
+    NoMatch,
+    // Some file couldn't be searched; the others still were.
+    Error,
+}
+
+impl Status {
+    fn found(found: bool) -> Status {
+        if found {
+            Status::Matched
+        } else {
+            Status::NoMatch
+        }
+    }
+}
+
+pub enum Mode {
+    Search,
+    // Search only the lines that differ between two versions of a file.
+    Compare,
+    Help,
+    Generate(Generate),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Sort {
+    Path,
+    Line,
+    None,
+}
+
+impl FromStr for Sort {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "path" => Ok(Sort::Path),
+            "line" => Ok(Sort::Line),
+            "none" => Ok(Sort::None),
+            _ => Err(format!(
+                "unknown sort order '{s}' (expected path, line or none)"
+            )),
+        }
+    }
+}
+
+pub struct Config {
+    pub mode: Mode,
+    pub query: String,
+    pub paths: Vec<String>,
+    pub ignore_case: bool,
+    pub regex: bool,
+    pub boolean: bool,
+    pub fuzzy: Option<usize>,
+    pub stats: Option<StatsFormat>,
+    pub binary_hexdump: bool,
+    pub backend: Backend,
+    pub records: Records,
+    pub max_count: Option<usize>,
+    pub max_total: Option<usize>,
+    pub sort: Sort,
+    pub unique: bool,
+    pub column: bool,
+    pub watch: bool,
+    pub quiet: bool,
+    pub filter: FileFilter,
+}
+
+impl Config {
+    pub fn build(mut args: impl Iterator<Item = String>) -> Result<Config, String> {
+        // The first value is the name of the program.
+        args.next();
+
+        let parsed = cli::parse(args)?;
+
+        let mut mode = Mode::Search;
+        let mut ignore_case = env::var("IGNORE_CASE").is_ok();
+        let mut regex = false;
+        let mut boolean = false;
+        let mut fuzzy = None;
+        let mut stats = None;
+        let mut generate = None;
+        let mut binary_hexdump = false;
+        let mut backend = Backend::Auto;
+        let mut records = Records::Lines;
+        let mut max_count = None;
+        let mut max_total = None;
+        let mut sort = Sort::None;
+        let mut unique = false;
+        let mut column = false;
+        let mut watch = false;
+        let mut quiet = false;
+        let mut filter = FileFilter::default();
+
+        for (flag, value) in parsed.flags {
+            match flag.long {
+                "ignore-case" => ignore_case = true,
+                "regex" => regex = true,
+                "bool" => boolean = true,
+                "fuzzy" => fuzzy = Some(number(flag.long, value)?),
+                "stats" => {
+                    stats = Some(match value {
+                        Some(format) => format.parse()?,
+                        None => StatsFormat::Human,
+                    });
+                }
+                "binary-hexdump" => binary_hexdump = true,
+                "mmap" => backend = Backend::Mmap,
+                "no-mmap" => backend = Backend::Buffered,
+                "paragraph" => records = Records::paragraphs(),
+                "record-separator" => {
+                    let separator = value.unwrap_or_default();
+                    records = Records::separated_by(&separator)
+                        .map_err(|e| format!("invalid record separator: {e}"))?;
+                }
+                "max-count" => max_count = Some(number(flag.long, value)?),
+                "max-total" => max_total = Some(number(flag.long, value)?),
+                "sort" => sort = value.unwrap_or_default().parse()?,
+                "unique" => unique = true,
+                "column" => column = true,
+                "watch" => watch = true,
+                "quiet" => quiet = true,
+                "max-filesize" => {
+                    filter.max_filesize = Some(filter::parse_size(&value.unwrap_or_default())?);
+                }
+                "newer-than" => {
+                    filter.newer_than = Some(filter::parse_age(&value.unwrap_or_default())?);
+                }
+                "type" => {
+                    let name = value.unwrap_or_default();
+                    let mut types = filter::types()?;
+                    match types.remove(&name) {
+                        Some(extensions) => filter.extensions.extend(extensions),
+                        None => {
+                            let known: Vec<String> = types.into_keys().collect();
+                            return Err(format!(
+                                "unknown file type '{name}' (expected {})",
+                                known.join(", ")
+                            ));
+                        }
+                    }
+                }
+                "compare" => mode = Mode::Compare,
+                "generate" => generate = value,
+                "help" => mode = Mode::Help,
+                long => unreachable!("option '--{long}' is not handled"),
+            }
+        }
+
+        let mut positional = parsed.positional.into_iter();
+
+        if let (Mode::Search, Some(kind)) = (&mode, generate) {
+            mode = Mode::Generate(match kind.as_str() {
+                "man" => Generate::Man,
+                _ => match positional.next() {
+                    Some(shell) => Generate::Completions(shell.parse()?),
+                    None => {
+                        return Err("Didn't get a shell to generate completions for".to_string())
+                    }
+                },
+            });
+        }
+
+        let (query, paths) = match mode {
+            Mode::Search => {
+                let query = match positional.next() {
+                    Some(arg) => arg,
+                    None => return Err("Didn't get a query string".to_string()),
+                };
+
+                let paths: Vec<String> = positional.collect();
+                if paths.is_empty() {
+                    return Err("Didn't get a file name".to_string());
+                }
+
+                (query, paths)
+            }
+            Mode::Compare => match (positional.next(), positional.next(), positional.next()) {
+                (Some(old), Some(new), Some(query)) => (query, vec![old, new]),
+                _ => return Err("--compare needs OLD NEW QUERY".to_string()),
+            },
+            Mode::Help | Mode::Generate(_) => (String::new(), Vec::new()),
+        };
+
+        Ok(Config {
+            mode,
+            query,
+            paths,
+            ignore_case,
+            regex,
+            boolean,
+            fuzzy,
+            stats,
+            binary_hexdump,
+            backend,
+            records,
+            max_count,
+            max_total,
+            sort,
+            unique,
+            column,
+            watch,
+            quiet,
+            filter,
+        })
+    }
+
+    pub fn matcher(&self) -> Result<Box<dyn Matcher>, Box<dyn Error>> {
+        let options = MatcherOptions {
+            ignore_case: self.ignore_case,
+            regex: self.regex,
+            fuzzy: self.fuzzy,
+            boolean: self.boolean,
+        };
+        matcher::build(&self.query, options)
+    }
+}
+
+fn number(flag: &str, value: Option<String>) -> Result<usize, String> {
+    let value = value.unwrap_or_default();
+    value
+        .parse()
+        .map_err(|_| format!("invalid number '{value}' for '--{flag}'"))
+}
+
+pub fn run(config: Config) -> Result<Status, Box<dyn Error>> {
+    match config.mode {
+        Mode::Search => {}
+        Mode::Help => {
+            print!("{}", generate::help());
+            return Ok(Status::Matched);
+        }
+        Mode::Generate(kind) => {
+            print!("{}", kind.render());
+            return Ok(Status::Matched);
+        }
+        Mode::Compare => return compare(&config).map(Status::found),
+    }
+
+    if !config.watch {
+        return search_paths(&config);
+    }
+
+    loop {
+        // Start watching before searching, so changes made during the search
+        // aren't missed.
+        let mut watcher = Watcher::new(&config.paths);
+
+        print!("{CLEAR_SCREEN}");
+        if let Err(e) = search_paths(&config) {
+            eprintln!("Application error: {e}");
+        }
+
+        watcher.wait()?;
+    }
+}
+
+fn search_paths(config: &Config) -> Result<Status, Box<dyn Error>> {
+    let start = Instant::now();
+
+    let mut skipped = Vec::new();
+    let mut paths = walk::files(&config.paths, &config.filter, &mut skipped);
+    for message in &skipped {
+        eprintln!("Application error: {message}");
+    }
+    if config.sort == Sort::Path {
+        paths.sort();
+    }
+
+    let mut searcher = Searcher::new(config.matcher()?);
+    searcher.records = config.records.clone();
+    searcher.max_count = config.max_count;
+    searcher.max_total = config.max_total;
+    if config.quiet {
+        // The first match settles the exit status; nothing else is needed.
+        searcher.max_total = Some(1);
+    }
+
+    let mut stats = Stats::default();
+    let mut printer = Printer::new(io::stdout().lock());
+    printer.color = io::stdout().is_terminal();
+    // Files found in a directory or archive are always labelled with where
+    // they came from.
+    printer.show_path = paths.len() > 1
+        || config.paths.iter().any(|path| Path::new(path).is_dir())
+        || paths.iter().any(|path| tar::is_archive(path));
+    printer.sort = config.sort;
+    printer.unique = config.unique;
+    printer.binary_hexdump = config.binary_hexdump;
+    printer.column = config.column;
+
+    let sink: &mut dyn Sink = if config.quiet {
+        &mut Discard
+    } else if config.stats.is_some() {
+        &mut stats
+    } else {
+        &mut printer
+    };
+
+    let mut failed = !skipped.is_empty();
+    for path in &paths {
+        if searcher.is_done() {
+            break;
+        }
+        // Like grep, a file that can't be read is reported and the rest are
+        // still searched. Failing to write the output stops everything; those
+        // are the only bare `io::Error`s, as read errors carry their path.
+        match searcher.search_path(path, config.backend, sink) {
+            Err(e) if e.is::<io::Error>() => return Err(e),
+            Err(e) => {
+                eprintln!("Application error: {e}");
+                failed = true;
+            }
+            Ok(()) => {}
+        }
+    }
+    sink.finish()?;
+
+    if let (Some(format), false) = (config.stats, config.quiet) {
+        stats.elapsed = start.elapsed();
+        print!("{}", stats.render(format));
+    }
+
+    let found = searcher.found() > 0;
+    // Quiet mode only asks whether something matched, so that wins out.
+    if failed && !(config.quiet && found) {
+        return Ok(Status::Error);
+    }
+    Ok(Status::found(found))
+}
+
+// Prints the matching lines that were added (`+`) or removed (`-`) between
+// the two versions of a file.
+fn compare(config: &Config) -> Result<bool, Box<dyn Error>> {
+    let matcher = config.matcher()?;
+    let color = io::stdout().is_terminal();
+
+    let old = reader::read(&config.paths[0], config.backend)
+        .map_err(|e| format!("{}: {e}", config.paths[0]))?;
+    let new = reader::read(&config.paths[1], config.backend)
+        .map_err(|e| format!("{}: {e}", config.paths[1]))?;
+    let old = String::from_utf8_lossy(&old);
+    let new = String::from_utf8_lossy(&new);
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+
+    let mut out = io::stdout().lock();
+    let mut found = false;
+
+    for edit in diff::diff(&old, &new) {
+        let (sign, sign_color, m) = match edit {
+            Edit::Keep { .. } => continue,
+            Edit::Remove(i) => ('-', RED, match_line(matcher.as_ref(), i + 1, old[i])),
+            Edit::Add(i) => ('+', GREEN, match_line(matcher.as_ref(), i + 1, new[i])),
+        };
+
+        let Some(m) = m else { continue };
+        found = true;
+
+        if config.quiet {
+            break;
+        } else if color {
+            writeln!(
+                out,
+                "{sign_color}{sign}{END}{CYAN}{}{END}:{}",
+                m.line_number,
+                highlight(m.line, &m.spans)
+            )?;
+        } else {
+            writeln!(out, "{sign}{}:{}", m.line_number, m.line)?;
+        }
+    }
+
+    Ok(found)
+}
@@ -1,30 +1,23 @@
 use std::env;
-use std::fs;
+use std::process;
 
-fn main() {
-    // let reversed = "\u{001b}[7m";
-    // let red = "\u{001b}[31m";
-    // let green = "\u{001b}[32m;1m";
-    // let yellow = "\u{001b}[33m";
-    // let blue = "\u{001b}[34m";
-    // let white = "\u{001b}[37m";
-    let bold = "\u{001b}[1m";
-    let magenta = "\u{001b}[35m";
-    let cyan = "\u{001b}[36m";
-    let end = "\u{001b}[0m";
-
-    let args: Vec<String> = env::args().collect();
-    
-    let query = &args[1];
-    let filename = &args[2];
-
-    let contents = fs::read_to_string(filename)
-        .expect("Something wnt wrong reading the file");
+use minigrep::{Config, Status};
 
+fn main() {
+    let config = Config::build(env::args()).unwrap_or_else(|err| {
+        eprintln!("Problem parsing arguments: {err}");
+        process::exit(2);
+    });
 
-    println!("{:?}", args);
-    println!("Searching for: {cyan}{bold}{}{end}", query);
-    println!("In file: {magenta}{bold}{}{end}", filename);
-    println!("File content:\n\n{}", contents);
-
+    // Like grep: 0 when something matched, 1 when nothing did, 2 on errors.
+    match minigrep::run(config) {
+        Ok(Status::Matched) => {}
+        Ok(Status::NoMatch) => process::exit(1),
+        // The error has been reported already.
+        Ok(Status::Error) => process::exit(2),
+        Err(e) => {
+            eprintln!("Application error: {e}");
+            process::exit(2);
+        }
+    }
 }
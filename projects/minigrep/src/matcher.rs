@@ -0,0 +1,497 @@
+use std::error::Error;
+
+use regex::{bytes, Regex, RegexBuilder};
+
+use crate::query::{self, Expr};
+
+// Finds what a searcher is looking for in a line or record of text, and in
+// the raw contents of binary files.
+pub trait Matcher {
+    // The byte ranges to highlight in `text`, in order and not overlapping,
+    // or `None` if it doesn't match. A match can come without any ranges, as
+    // for the query `NOT dreary`.
+    fn find(&self, text: &str) -> Option<Vec<(usize, usize)>>;
+
+    // The same as `find`, over bytes that aren't necessarily UTF-8.
+    fn find_bytes(&self, bytes: &[u8]) -> Option<Vec<(usize, usize)>>;
+
+    fn is_match(&self, text: &str) -> bool {
+        self.find(text).is_some()
+    }
+
+    fn is_match_bytes(&self, bytes: &[u8]) -> bool {
+        self.find_bytes(bytes).is_some()
+    }
+}
+
+fn found(spans: Vec<(usize, usize)>) -> Option<Vec<(usize, usize)>> {
+    (!spans.is_empty()).then_some(spans)
+}
+
+// Sorts spans and joins the overlapping ones, so they can be highlighted in a
+// single pass.
+fn merge(mut spans: Vec<(usize, usize)>) -> Vec<(usize, usize)> {
+    spans.sort();
+
+    let mut merged: Vec<(usize, usize)> = Vec::with_capacity(spans.len());
+    for (start, end) in spans {
+        match merged.last_mut() {
+            Some(last) if start <= last.1 => last.1 = last.1.max(end),
+            _ => merged.push((start, end)),
+        }
+    }
+    merged
+}
+
+// Non-overlapping occurrences of `needle`, using `eq` to compare units.
+fn find_units<T>(needle: &[T], haystack: &[T], eq: impl Fn(&T, &T) -> bool) -> Vec<(usize, usize)> {
+    let mut spans = Vec::new();
+    let mut start = 0;
+
+    while start + needle.len() <= haystack.len() {
+        let window = &haystack[start..start + needle.len()];
+        if window.iter().zip(needle).all(|(a, b)| eq(a, b)) {
+            spans.push((start, start + needle.len()));
+            start += needle.len();
+        } else {
+            start += 1;
+        }
+    }
+
+    spans
+}
+
+// Splits text into chars, remembering the byte offset each one starts at so
+// that char positions can be turned back into byte ranges.
+fn chars_with_offsets(text: &str) -> (Vec<char>, Vec<usize>) {
+    let mut offsets: Vec<usize> = text.char_indices().map(|(i, _)| i).collect();
+    let chars = text.chars().collect();
+    offsets.push(text.len());
+    (chars, offsets)
+}
+
+// Matches the query text exactly.
+pub struct Literal {
+    needle: String,
+}
+
+impl Literal {
+    pub fn new(needle: &str) -> Literal {
+        Literal {
+            needle: needle.to_string(),
+        }
+    }
+}
+
+impl Matcher for Literal {
+    fn find(&self, text: &str) -> Option<Vec<(usize, usize)>> {
+        // Like grep, an empty query matches every line.
+        if self.needle.is_empty() {
+            return Some(Vec::new());
+        }
+
+        found(
+            text.match_indices(self.needle.as_str())
+                .map(|(start, m)| (start, start + m.len()))
+                .collect(),
+        )
+    }
+
+    fn find_bytes(&self, bytes: &[u8]) -> Option<Vec<(usize, usize)>> {
+        if self.needle.is_empty() {
+            return Some(Vec::new());
+        }
+
+        found(find_units(self.needle.as_bytes(), bytes, |a, b| a == b))
+    }
+}
+
+fn same_ignoring_case(a: &char, b: &char) -> bool {
+    a == b || a.to_lowercase().eq(b.to_lowercase())
+}
+
+// Matches the query text regardless of case, comparing a char at a time.
+// Binary files are compared ignoring ASCII case only.
+pub struct CaseInsensitive {
+    needle: Vec<char>,
+    needle_bytes: Vec<u8>,
+}
+
+impl CaseInsensitive {
+    pub fn new(needle: &str) -> CaseInsensitive {
+        CaseInsensitive {
+            needle: needle.chars().collect(),
+            needle_bytes: needle.as_bytes().to_vec(),
+        }
+    }
+}
+
+impl Matcher for CaseInsensitive {
+    fn find(&self, text: &str) -> Option<Vec<(usize, usize)>> {
+        if self.needle.is_empty() {
+            return Some(Vec::new());
+        }
+
+        let (chars, offsets) = chars_with_offsets(text);
+        found(
+            find_units(&self.needle, &chars, same_ignoring_case)
+                .into_iter()
+                .map(|(start, end)| (offsets[start], offsets[end]))
+                .collect(),
+        )
+    }
+
+    fn find_bytes(&self, bytes: &[u8]) -> Option<Vec<(usize, usize)>> {
+        if self.needle_bytes.is_empty() {
+            return Some(Vec::new());
+        }
+
+        found(find_units(
+            &self.needle_bytes,
+            bytes,
+            u8::eq_ignore_ascii_case,
+        ))
+    }
+}
+
+// Matches a regular expression, compiled once for text and once for bytes.
+pub struct RegexMatcher {
+    text: Regex,
+    bytes: bytes::Regex,
+}
+
+impl RegexMatcher {
+    pub fn new(pattern: &str, ignore_case: bool) -> Result<RegexMatcher, regex::Error> {
+        Ok(RegexMatcher {
+            text: RegexBuilder::new(pattern)
+                .case_insensitive(ignore_case)
+                .build()?,
+            bytes: bytes::RegexBuilder::new(pattern)
+                .case_insensitive(ignore_case)
+                .build()?,
+        })
+    }
+}
+
+impl Matcher for RegexMatcher {
+    fn find(&self, text: &str) -> Option<Vec<(usize, usize)>> {
+        found(
+            self.text
+                .find_iter(text)
+                .map(|m| (m.start(), m.end()))
+                .collect(),
+        )
+    }
+
+    fn find_bytes(&self, bytes: &[u8]) -> Option<Vec<(usize, usize)>> {
+        found(
+            self.bytes
+                .find_iter(bytes)
+                .map(|m| (m.start(), m.end()))
+                .collect(),
+        )
+    }
+
+    fn is_match(&self, text: &str) -> bool {
+        self.text.is_match(text)
+    }
+
+    fn is_match_bytes(&self, bytes: &[u8]) -> bool {
+        self.bytes.is_match(bytes)
+    }
+}
+
+// Matches anything within `max_edits` insertions, deletions or substitutions
+// of the query text, so `frog` with one edit also finds `frogs` and `from`.
+pub struct Fuzzy {
+    needle: Vec<char>,
+    max_edits: usize,
+    ignore_case: bool,
+}
+
+impl Fuzzy {
+    pub fn new(needle: &str, max_edits: usize, ignore_case: bool) -> Fuzzy {
+        Fuzzy {
+            needle: needle.chars().collect(),
+            max_edits,
+            ignore_case,
+        }
+    }
+}
+
+// Sellers' algorithm: edit distance where the match may start anywhere in
+// the haystack. Alongside each cost we track where that alignment started,
+// so every end position within budget gives a span.
+fn approximate<T>(
+    needle: &[T],
+    haystack: &[T],
+    max_edits: usize,
+    eq: impl Fn(&T, &T) -> bool,
+) -> Vec<(usize, usize)> {
+    let mut cost: Vec<usize> = (0..=needle.len()).collect();
+    let mut start = vec![0; needle.len() + 1];
+    let mut spans = Vec::new();
+
+    for (j, unit) in haystack.iter().enumerate() {
+        // The values from the previous column, one row up.
+        let mut diagonal = (cost[0], start[0]);
+        cost[0] = 0;
+        start[0] = j + 1;
+
+        for i in 1..=needle.len() {
+            let substitute = diagonal.0 + usize::from(!eq(&needle[i - 1], unit));
+            let candidates = [
+                (substitute, diagonal.1),
+                (cost[i - 1] + 1, start[i - 1]),
+                (cost[i] + 1, start[i]),
+            ];
+            let best = candidates.into_iter().min_by_key(|&(c, _)| c).unwrap();
+
+            diagonal = (cost[i], start[i]);
+            cost[i] = best.0;
+            start[i] = best.1;
+        }
+
+        let last = needle.len();
+        if cost[last] <= max_edits && start[last] <= j {
+            spans.push((start[last], j + 1));
+        }
+    }
+
+    merge(spans)
+}
+
+impl Matcher for Fuzzy {
+    fn find(&self, text: &str) -> Option<Vec<(usize, usize)>> {
+        if self.needle.is_empty() {
+            return Some(Vec::new());
+        }
+
+        let (chars, offsets) = chars_with_offsets(text);
+        let spans = if self.ignore_case {
+            approximate(&self.needle, &chars, self.max_edits, same_ignoring_case)
+        } else {
+            approximate(&self.needle, &chars, self.max_edits, char::eq)
+        };
+
+        found(
+            spans
+                .into_iter()
+                .map(|(start, end)| (offsets[start], offsets[end]))
+                .collect(),
+        )
+    }
+
+    fn find_bytes(&self, bytes: &[u8]) -> Option<Vec<(usize, usize)>> {
+        if self.needle.is_empty() {
+            return Some(Vec::new());
+        }
+
+        let needle: Vec<u8> = self.needle.iter().collect::<String>().into_bytes();
+        found(if self.ignore_case {
+            approximate(&needle, bytes, self.max_edits, u8::eq_ignore_ascii_case)
+        } else {
+            approximate(&needle, bytes, self.max_edits, u8::eq)
+        })
+    }
+}
+
+// Combines other matchers with AND, OR and NOT; see the `query` module.
+pub struct QueryMatcher {
+    expr: Expr,
+    terms: Vec<Box<dyn Matcher>>,
+}
+
+impl QueryMatcher {
+    pub fn new(expr: Expr, terms: Vec<Box<dyn Matcher>>) -> QueryMatcher {
+        QueryMatcher { expr, terms }
+    }
+}
+
+impl Matcher for QueryMatcher {
+    fn find(&self, text: &str) -> Option<Vec<(usize, usize)>> {
+        if !self.expr.eval(&|i| self.terms[i].is_match(text)) {
+            return None;
+        }
+
+        let spans = self
+            .expr
+            .positive_terms()
+            .into_iter()
+            .flat_map(|i| self.terms[i].find(text).unwrap_or_default());
+        Some(merge(spans.collect()))
+    }
+
+    fn find_bytes(&self, bytes: &[u8]) -> Option<Vec<(usize, usize)>> {
+        if !self.expr.eval(&|i| self.terms[i].is_match_bytes(bytes)) {
+            return None;
+        }
+
+        let spans = self
+            .expr
+            .positive_terms()
+            .into_iter()
+            .flat_map(|i| self.terms[i].find_bytes(bytes).unwrap_or_default());
+        Some(merge(spans.collect()))
+    }
+}
+
+// How the query given on the command line should be interpreted.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MatcherOptions {
+    pub ignore_case: bool,
+    pub regex: bool,
+    pub fuzzy: Option<usize>,
+    pub boolean: bool,
+}
+
+fn term(text: &str, options: MatcherOptions) -> Result<Box<dyn Matcher>, regex::Error> {
+    Ok(if options.regex {
+        Box::new(RegexMatcher::new(text, options.ignore_case)?)
+    } else if let Some(max_edits) = options.fuzzy {
+        Box::new(Fuzzy::new(text, max_edits, options.ignore_case))
+    } else if options.ignore_case {
+        Box::new(CaseInsensitive::new(text))
+    } else {
+        Box::new(Literal::new(text))
+    })
+}
+
+// Builds the matcher for `query`. With `boolean` set the query is parsed as
+// an expression, and the other options apply to each of its terms.
+pub fn build(query: &str, options: MatcherOptions) -> Result<Box<dyn Matcher>, Box<dyn Error>> {
+    if !options.boolean {
+        return Ok(term(query, options)?);
+    }
+
+    let parsed = query::parse(query)?;
+    let terms = parsed
+        .terms
+        .iter()
+        .map(|text| term(text, options))
+        .collect::<Result<_, _>>()?;
+
+    Ok(Box::new(QueryMatcher::new(parsed.expr, terms)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spans<'a>(matcher: &dyn Matcher, text: &'a str) -> Option<Vec<&'a str>> {
+        matcher
+            .find(text)
+            .map(|spans| spans.into_iter().map(|(s, e)| &text[s..e]).collect())
+    }
+
+    #[test]
+    fn literal() {
+        let matcher = Literal::new("nobody");
+
+        assert_eq!(
+            spans(&matcher, "nobody, nobody"),
+            Some(vec!["nobody", "nobody"])
+        );
+        assert_eq!(spans(&matcher, "Nobody"), None);
+        assert_eq!(matcher.find_bytes(b"\0nobody\0"), Some(vec![(1, 7)]));
+    }
+
+    #[test]
+    fn literal_does_not_treat_query_as_regex() {
+        assert_eq!(spans(&Literal::new("a.c"), "abc"), None);
+        assert_eq!(spans(&Literal::new("a.c"), "a.c"), Some(vec!["a.c"]));
+    }
+
+    #[test]
+    fn empty_query_matches_everything() {
+        assert_eq!(Literal::new("").find("anything"), Some(vec![]));
+        assert_eq!(CaseInsensitive::new("").find(""), Some(vec![]));
+    }
+
+    #[test]
+    fn case_insensitive() {
+        let matcher = CaseInsensitive::new("rUsT");
+
+        assert_eq!(
+            spans(&matcher, "Rust: rust TRUST"),
+            Some(vec!["Rust", "rust", "RUST"])
+        );
+        assert_eq!(
+            spans(&CaseInsensitive::new("ÉTÉ"), "un été chaud"),
+            Some(vec!["été"])
+        );
+        assert_eq!(matcher.find_bytes(b"\0RUST\0"), Some(vec![(1, 5)]));
+    }
+
+    #[test]
+    fn regex() {
+        let matcher = RegexMatcher::new(r"\b[bl]og\b", false).unwrap();
+
+        assert_eq!(
+            spans(&matcher, "a log in a bog, logs"),
+            Some(vec!["log", "bog"])
+        );
+        assert!(RegexMatcher::new("(", false).is_err());
+    }
+
+    #[test]
+    fn fuzzy() {
+        let matcher = Fuzzy::new("frog", 1, false);
+
+        assert_eq!(spans(&matcher, "a frg sat"), Some(vec!["frg"]));
+        assert_eq!(spans(&matcher, "fraog"), Some(vec!["fraog"]));
+        assert_eq!(spans(&matcher, "a fog"), Some(vec!["fog"]));
+        assert_eq!(spans(&matcher, "a dog"), None);
+        assert_eq!(spans(&Fuzzy::new("frog", 0, false), "frg"), None);
+        assert_eq!(
+            spans(&Fuzzy::new("FROG", 1, true), "frag"),
+            Some(vec!["frag"])
+        );
+    }
+
+    #[test]
+    fn fuzzy_reports_byte_offsets() {
+        let matcher = Fuzzy::new("café", 1, false);
+
+        assert_eq!(spans(&matcher, "le cafe 😻"), Some(vec!["cafe"]));
+    }
+
+    #[test]
+    fn query() {
+        let options = MatcherOptions {
+            ignore_case: true,
+            boolean: true,
+            ..MatcherOptions::default()
+        };
+        let matcher = build("nobody AND NOT too", options).unwrap();
+
+        assert_eq!(
+            spans(matcher.as_ref(), "I'm Nobody! Who are you?"),
+            Some(vec!["Nobody"])
+        );
+        assert_eq!(spans(matcher.as_ref(), "Are you nobody, too?"), None);
+    }
+
+    #[test]
+    fn query_matches_without_highlights() {
+        let options = MatcherOptions {
+            boolean: true,
+            ..MatcherOptions::default()
+        };
+        let matcher = build("NOT dreary", options).unwrap();
+
+        assert_eq!(spans(matcher.as_ref(), "a frog"), Some(vec![]));
+        assert_eq!(spans(matcher.as_ref(), "How dreary"), None);
+    }
+
+    #[test]
+    fn overlapping_query_spans_are_merged() {
+        let options = MatcherOptions {
+            boolean: true,
+            ..MatcherOptions::default()
+        };
+        let matcher = build("some OR body", options).unwrap();
+
+        assert_eq!(spans(matcher.as_ref(), "somebody"), Some(vec!["somebody"]));
+    }
+}
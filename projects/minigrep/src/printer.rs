@@ -0,0 +1,275 @@
+use std::collections::HashMap;
+use std::io::{self, Write};
+
+use crate::binary;
+use crate::color::{BOLD, CYAN, END, MAGENTA};
+use crate::searcher::{Match, Sink};
+use crate::unicode;
+use crate::Sort;
+
+pub fn highlight(line: &str, spans: &[(usize, usize)]) -> String {
+    let mut highlighted = String::with_capacity(line.len());
+    let mut last = 0;
+
+    for (start, end) in unicode::snap_to_graphemes(line, spans) {
+        highlighted.push_str(&line[last..start]);
+        highlighted.push_str(BOLD);
+        highlighted.push_str(CYAN);
+        highlighted.push_str(&line[start..end]);
+        highlighted.push_str(END);
+        last = end;
+    }
+
+    highlighted.push_str(&line[last..]);
+    highlighted
+}
+
+// A match copied out of its file, for output that has to be reordered.
+struct Hit {
+    path: String,
+    line_number: usize,
+    line: String,
+    spans: Vec<(usize, usize)>,
+}
+
+// The sink behind minigrep's own output: `path:line:text`, coloured when
+// writing to a terminal.
+pub struct Printer<W: Write> {
+    out: W,
+    pub color: bool,
+    pub show_path: bool,
+    pub sort: Sort,
+    pub unique: bool,
+    pub binary_hexdump: bool,
+    pub column: bool,
+    hits: Vec<Hit>,
+}
+
+impl<W: Write> Printer<W> {
+    pub fn new(out: W) -> Printer<W> {
+        Printer {
+            out,
+            color: false,
+            show_path: false,
+            sort: Sort::None,
+            unique: false,
+            binary_hexdump: false,
+            column: false,
+            hits: Vec::new(),
+        }
+    }
+
+    // Sorting by line or collapsing duplicates needs every match before
+    // anything can be printed; otherwise lines are printed as they're found.
+    fn buffered(&self) -> bool {
+        self.sort == Sort::Line || self.unique
+    }
+
+    fn write_line(
+        &mut self,
+        path: &str,
+        line_number: usize,
+        line: &str,
+        spans: &[(usize, usize)],
+    ) -> io::Result<()> {
+        if self.show_path {
+            if self.color {
+                write!(self.out, "{MAGENTA}{path}{END}:")?;
+            } else {
+                write!(self.out, "{path}:")?;
+            }
+        }
+
+        if self.color {
+            write!(self.out, "{CYAN}{line_number}{END}:")?;
+        } else {
+            write!(self.out, "{line_number}:")?;
+        }
+
+        if self.column {
+            // A query like `NOT dreary` matches without a position to point at.
+            let offset = spans.first().map_or(0, |&(start, _)| start);
+            write!(self.out, "{}:", unicode::column(line, offset))?;
+        }
+
+        if self.color {
+            writeln!(self.out, "{}", highlight(line, spans))
+        } else {
+            writeln!(self.out, "{line}")
+        }
+    }
+}
+
+impl<W: Write> Sink for Printer<W> {
+    fn matched(&mut self, path: &str, m: &Match) -> io::Result<()> {
+        if self.buffered() {
+            self.hits.push(Hit {
+                path: path.to_string(),
+                line_number: m.line_number,
+                line: m.line.to_string(),
+                spans: m.spans.clone(),
+            });
+            return Ok(());
+        }
+
+        self.write_line(path, m.line_number, m.line, &m.spans)
+    }
+
+    fn binary_matched(
+        &mut self,
+        path: &str,
+        bytes: &[u8],
+        spans: &[(usize, usize)],
+    ) -> io::Result<()> {
+        if !self.binary_hexdump {
+            return writeln!(self.out, "Binary file {path} matches");
+        }
+
+        for &span in spans {
+            writeln!(self.out, "{path}: match at offset {:#010x}", span.0)?;
+            write!(self.out, "{}", binary::hexdump(bytes, span, self.color))?;
+        }
+
+        Ok(())
+    }
+
+    fn finish(&mut self) -> io::Result<()> {
+        let mut hits = std::mem::take(&mut self.hits);
+
+        if self.sort == Sort::Line {
+            // The sort is stable, so lines sharing a number keep their file order.
+            hits.sort_by_key(|hit| hit.line_number);
+        }
+
+        if self.unique {
+            let mut counts: Vec<(Hit, usize)> = Vec::new();
+            let mut seen: HashMap<String, usize> = HashMap::new();
+
+            for hit in hits {
+                match seen.get(&hit.line) {
+                    Some(&i) => counts[i].1 += 1,
+                    None => {
+                        seen.insert(hit.line.clone(), counts.len());
+                        counts.push((hit, 1));
+                    }
+                }
+            }
+
+            for (hit, count) in counts {
+                if self.color {
+                    writeln!(self.out, "{count:>7} {}", highlight(&hit.line, &hit.spans))?;
+                } else {
+                    writeln!(self.out, "{count:>7} {}", hit.line)?;
+                }
+            }
+        } else {
+            for hit in &hits {
+                self.write_line(&hit.path, hit.line_number, &hit.line, &hit.spans)?;
+            }
+        }
+
+        self.out.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matched(printer: &mut Printer<Vec<u8>>, path: &str, line_number: usize, line: &str) {
+        let m = Match {
+            line_number,
+            line,
+            spans: vec![(0, 1)],
+        };
+        printer.matched(path, &m).unwrap();
+    }
+
+    fn output(printer: Printer<Vec<u8>>) -> String {
+        String::from_utf8(printer.out).unwrap()
+    }
+
+    #[test]
+    fn prints_as_found() {
+        let mut printer = Printer::new(Vec::new());
+        printer.show_path = true;
+        matched(&mut printer, "b", 2, "two");
+        matched(&mut printer, "a", 1, "one");
+        printer.finish().unwrap();
+
+        assert_eq!(output(printer), "b:2:two\na:1:one\n");
+    }
+
+    #[test]
+    fn highlights_in_color() {
+        let mut printer = Printer::new(Vec::new());
+        printer.color = true;
+        matched(&mut printer, "a", 1, "one");
+
+        assert_eq!(
+            output(printer),
+            format!("{CYAN}1{END}:{BOLD}{CYAN}o{END}ne\n")
+        );
+    }
+
+    #[test]
+    fn sorts_by_line() {
+        let mut printer = Printer::new(Vec::new());
+        printer.sort = Sort::Line;
+        matched(&mut printer, "a", 3, "three");
+        matched(&mut printer, "b", 1, "one");
+        matched(&mut printer, "a", 1, "uno");
+
+        assert_eq!(printer.out.len(), 0);
+        printer.finish().unwrap();
+        assert_eq!(output(printer), "1:one\n1:uno\n3:three\n");
+    }
+
+    #[test]
+    fn counts_unique_lines() {
+        let mut printer = Printer::new(Vec::new());
+        printer.unique = true;
+        matched(&mut printer, "a", 1, "same");
+        matched(&mut printer, "a", 2, "other");
+        matched(&mut printer, "b", 7, "same");
+        printer.finish().unwrap();
+
+        assert_eq!(output(printer), "      2 same\n      1 other\n");
+    }
+
+    #[test]
+    fn highlights_whole_graphemes() {
+        // "e" followed by a combining acute accent, then a cat emoji followed
+        // by a variation selector. Both spans start or end mid-grapheme.
+        let line = "cafe\u{301} 😻\u{fe0f}";
+        let highlighted = highlight(line, &[(3, 4), (8, 10)]);
+
+        assert_eq!(
+            highlighted,
+            format!("caf{BOLD}{CYAN}e\u{301}{END} {BOLD}{CYAN}😻\u{fe0f}{END}")
+        );
+    }
+
+    #[test]
+    fn columns_count_graphemes() {
+        let mut printer = Printer::new(Vec::new());
+        printer.column = true;
+        let line = "😻 cafe\u{301} café";
+        let m = Match {
+            line_number: 1,
+            line,
+            spans: vec![(line.rfind("café").unwrap(), line.len())],
+        };
+        printer.matched("a", &m).unwrap();
+
+        assert_eq!(output(printer), format!("1:8:{line}\n"));
+    }
+
+    #[test]
+    fn reports_binary_files() {
+        let mut printer = Printer::new(Vec::new());
+        printer.binary_matched("bin", b"\0frog", &[(1, 5)]).unwrap();
+
+        assert_eq!(output(printer), "Binary file bin matches\n");
+    }
+}
@@ -0,0 +1,459 @@
+// A small boolean query language for combining search terms:
+//
+//     nobody AND (frog OR bog) AND NOT dreary
+//
+// NOT binds tighter than AND, which binds tighter than OR. Operators must be
+// written in capitals, so `and` on its own is an ordinary term; a term
+// containing spaces, parentheses or a capitalised operator can be quoted, as
+// in "AND so on".
+
+use std::error::Error;
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Expr {
+    // An index into `Query::terms`.
+    Term(usize),
+    Not(Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+}
+
+impl Expr {
+    // Evaluates the expression, asking `matches` whether each term is present.
+    pub fn eval(&self, matches: &dyn Fn(usize) -> bool) -> bool {
+        match self {
+            Expr::Term(i) => matches(*i),
+            Expr::Not(expr) => !expr.eval(matches),
+            Expr::And(left, right) => left.eval(matches) && right.eval(matches),
+            Expr::Or(left, right) => left.eval(matches) || right.eval(matches),
+        }
+    }
+
+    // The terms whose presence helps a record match, i.e. those not under an
+    // odd number of NOTs. These are the ones worth highlighting.
+    pub fn positive_terms(&self) -> Vec<usize> {
+        let mut terms = Vec::new();
+        self.collect_positive(false, &mut terms);
+        terms
+    }
+
+    fn collect_positive(&self, negated: bool, terms: &mut Vec<usize>) {
+        match self {
+            Expr::Term(i) => {
+                if !negated {
+                    terms.push(*i);
+                }
+            }
+            Expr::Not(expr) => expr.collect_positive(!negated, terms),
+            Expr::And(left, right) | Expr::Or(left, right) => {
+                left.collect_positive(negated, terms);
+                right.collect_positive(negated, terms);
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Query {
+    pub expr: Expr,
+    pub terms: Vec<String>,
+}
+
+// Renders the query fully parenthesised, e.g. `(AND nobody (OR frog bog))`.
+impl fmt::Display for Query {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fn write(f: &mut fmt::Formatter, expr: &Expr, terms: &[String]) -> fmt::Result {
+            match expr {
+                Expr::Term(i) => write!(f, "{:?}", terms[*i]),
+                Expr::Not(expr) => {
+                    write!(f, "(NOT ")?;
+                    write(f, expr, terms)?;
+                    write!(f, ")")
+                }
+                Expr::And(left, right) | Expr::Or(left, right) => {
+                    let op = if let Expr::And(..) = expr {
+                        "AND"
+                    } else {
+                        "OR"
+                    };
+                    write!(f, "({op} ")?;
+                    write(f, left, terms)?;
+                    write!(f, " ")?;
+                    write(f, right, terms)?;
+                    write!(f, ")")
+                }
+            }
+        }
+
+        write(f, &self.expr, &self.terms)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub message: String,
+    // Byte offset into the query where the problem was found.
+    pub position: usize,
+    pub query: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let column = self.query[..self.position].chars().count();
+        writeln!(
+            f,
+            "invalid query: {} at column {}",
+            self.message,
+            column + 1
+        )?;
+        writeln!(f, "  {}", self.query)?;
+        write!(f, "  {}^", " ".repeat(column))
+    }
+}
+
+impl Error for ParseError {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Term(String),
+    And,
+    Or,
+    Not,
+    Open,
+    Close,
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Token::Term(term) => write!(f, "{term:?}"),
+            Token::And => write!(f, "AND"),
+            Token::Or => write!(f, "OR"),
+            Token::Not => write!(f, "NOT"),
+            Token::Open => write!(f, "'('"),
+            Token::Close => write!(f, "')'"),
+        }
+    }
+}
+
+fn tokenize(query: &str) -> Result<Vec<(Token, usize)>, ParseError> {
+    let mut tokens = Vec::new();
+    let mut chars = query.char_indices().peekable();
+
+    while let Some(&(start, c)) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c == '(' || c == ')' {
+            chars.next();
+            let token = if c == '(' { Token::Open } else { Token::Close };
+            tokens.push((token, start));
+        } else if c == '"' {
+            chars.next();
+            let unterminated = || ParseError {
+                message: "unterminated quoted term".to_string(),
+                position: start,
+                query: query.to_string(),
+            };
+            let mut term = String::new();
+            loop {
+                match chars.next() {
+                    Some((_, '"')) => break,
+                    Some((_, '\\')) => match chars.next() {
+                        Some((_, escaped)) => term.push(escaped),
+                        None => return Err(unterminated()),
+                    },
+                    Some((_, c)) => term.push(c),
+                    None => return Err(unterminated()),
+                }
+            }
+            tokens.push((Token::Term(term), start));
+        } else {
+            let mut word = String::new();
+            while let Some(&(_, c)) = chars.peek() {
+                if c.is_whitespace() || c == '(' || c == ')' || c == '"' {
+                    break;
+                }
+                word.push(c);
+                chars.next();
+            }
+
+            let token = match word.as_str() {
+                "AND" => Token::And,
+                "OR" => Token::Or,
+                "NOT" => Token::Not,
+                _ => Token::Term(word),
+            };
+            tokens.push((token, start));
+        }
+    }
+
+    Ok(tokens)
+}
+
+struct Parser<'a> {
+    query: &'a str,
+    tokens: Vec<(Token, usize)>,
+    next: usize,
+    terms: Vec<String>,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.next).map(|(token, _)| token)
+    }
+
+    fn position(&self) -> usize {
+        self.tokens
+            .get(self.next)
+            .map_or(self.query.len(), |&(_, position)| position)
+    }
+
+    fn error(&self, expected: &str) -> ParseError {
+        let found = match self.peek() {
+            Some(token) => token.to_string(),
+            None => "end of query".to_string(),
+        };
+
+        ParseError {
+            message: format!("expected {expected}, found {found}"),
+            position: self.position(),
+            query: self.query.to_string(),
+        }
+    }
+
+    // or := and ("OR" and)*
+    fn or(&mut self) -> Result<Expr, ParseError> {
+        let mut expr = self.and()?;
+        while self.peek() == Some(&Token::Or) {
+            self.next += 1;
+            expr = Expr::Or(Box::new(expr), Box::new(self.and()?));
+        }
+        Ok(expr)
+    }
+
+    // and := not ("AND" not)*
+    fn and(&mut self) -> Result<Expr, ParseError> {
+        let mut expr = self.not()?;
+        while self.peek() == Some(&Token::And) {
+            self.next += 1;
+            expr = Expr::And(Box::new(expr), Box::new(self.not()?));
+        }
+        Ok(expr)
+    }
+
+    // not := "NOT" not | atom
+    fn not(&mut self) -> Result<Expr, ParseError> {
+        if self.peek() == Some(&Token::Not) {
+            self.next += 1;
+            return Ok(Expr::Not(Box::new(self.not()?)));
+        }
+        self.atom()
+    }
+
+    // atom := TERM | "(" or ")"
+    fn atom(&mut self) -> Result<Expr, ParseError> {
+        match self.peek() {
+            Some(Token::Term(term)) => {
+                let term = term.clone();
+                self.next += 1;
+                self.terms.push(term);
+                Ok(Expr::Term(self.terms.len() - 1))
+            }
+            Some(Token::Open) => {
+                self.next += 1;
+                let expr = self.or()?;
+                if self.peek() != Some(&Token::Close) {
+                    return Err(self.error("')'"));
+                }
+                self.next += 1;
+                Ok(expr)
+            }
+            _ => Err(self.error("a term")),
+        }
+    }
+}
+
+pub fn parse(query: &str) -> Result<Query, ParseError> {
+    let mut parser = Parser {
+        query,
+        tokens: tokenize(query)?,
+        next: 0,
+        terms: Vec::new(),
+    };
+
+    let expr = parser.or()?;
+    if parser.peek().is_some() {
+        return Err(parser.error("AND or OR"));
+    }
+
+    Ok(Query {
+        expr,
+        terms: parser.terms,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parsed(query: &str) -> String {
+        parse(query).unwrap().to_string()
+    }
+
+    fn error(query: &str) -> (String, usize) {
+        let error = parse(query).unwrap_err();
+        (error.message, error.position)
+    }
+
+    #[test]
+    fn single_term() {
+        assert_eq!(parsed("nobody"), r#""nobody""#);
+    }
+
+    #[test]
+    fn and_binds_tighter_than_or() {
+        assert_eq!(
+            parsed("frog OR bog AND dreary"),
+            r#"(OR "frog" (AND "bog" "dreary"))"#
+        );
+        assert_eq!(
+            parsed("frog AND bog OR dreary"),
+            r#"(OR (AND "frog" "bog") "dreary")"#
+        );
+    }
+
+    #[test]
+    fn not_binds_tighter_than_and() {
+        assert_eq!(parsed("NOT frog AND bog"), r#"(AND (NOT "frog") "bog")"#);
+        assert_eq!(parsed("NOT NOT frog"), r#"(NOT (NOT "frog"))"#);
+    }
+
+    #[test]
+    fn operators_are_left_associative() {
+        assert_eq!(parsed("a OR b OR c"), r#"(OR (OR "a" "b") "c")"#);
+        assert_eq!(parsed("a AND b AND c"), r#"(AND (AND "a" "b") "c")"#);
+    }
+
+    #[test]
+    fn parentheses_override_precedence() {
+        assert_eq!(
+            parsed("nobody AND (frog OR bog) AND NOT dreary"),
+            r#"(AND (AND "nobody" (OR "frog" "bog")) (NOT "dreary"))"#
+        );
+    }
+
+    #[test]
+    fn lowercase_operators_are_terms() {
+        assert_eq!(parsed("(and)"), r#""and""#);
+    }
+
+    #[test]
+    fn quoted_terms() {
+        assert_eq!(
+            parsed(r#""to be" OR "AND" OR "say \"hi\"""#),
+            r#"(OR (OR "to be" "AND") "say \"hi\"")"#
+        );
+    }
+
+    #[test]
+    fn positive_terms_skip_negated_ones() {
+        let query = parse("nobody AND NOT (dreary OR NOT frog)").unwrap();
+        let positive: Vec<&str> = query
+            .expr
+            .positive_terms()
+            .into_iter()
+            .map(|i| query.terms[i].as_str())
+            .collect();
+
+        assert_eq!(positive, ["nobody", "frog"]);
+    }
+
+    #[test]
+    fn eval() {
+        let query = parse("nobody AND (frog OR bog) AND NOT dreary").unwrap();
+        let present = |words: &'static [&'static str]| {
+            let terms = query.terms.clone();
+            move |i: usize| words.contains(&terms[i].as_str())
+        };
+
+        assert!(query.expr.eval(&present(&["nobody", "bog"])));
+        assert!(!query.expr.eval(&present(&["nobody"])));
+        assert!(!query.expr.eval(&present(&["nobody", "frog", "dreary"])));
+    }
+
+    #[test]
+    fn empty_query() {
+        assert_eq!(
+            error(""),
+            ("expected a term, found end of query".to_string(), 0)
+        );
+        assert_eq!(
+            error("   "),
+            ("expected a term, found end of query".to_string(), 3)
+        );
+    }
+
+    #[test]
+    fn missing_operand() {
+        assert_eq!(
+            error("frog AND"),
+            ("expected a term, found end of query".to_string(), 8)
+        );
+        assert_eq!(
+            error("OR frog"),
+            ("expected a term, found OR".to_string(), 0)
+        );
+        assert_eq!(
+            error("frog AND )"),
+            ("expected a term, found ')'".to_string(), 9)
+        );
+    }
+
+    #[test]
+    fn unbalanced_parentheses() {
+        assert_eq!(
+            error("nobody AND (frog OR bog"),
+            ("expected ')', found end of query".to_string(), 23)
+        );
+        assert_eq!(
+            error("frog)"),
+            ("expected AND or OR, found ')'".to_string(), 4)
+        );
+    }
+
+    #[test]
+    fn missing_operator() {
+        assert_eq!(
+            error("frog bog"),
+            (r#"expected AND or OR, found "bog""#.to_string(), 5)
+        );
+    }
+
+    #[test]
+    fn unterminated_quote() {
+        assert_eq!(
+            error(r#"frog OR "bog"#),
+            ("unterminated quoted term".to_string(), 8)
+        );
+        // A trailing backslash escapes nothing, so the quote is still open.
+        assert_eq!(
+            error(r#""frog\"#),
+            ("unterminated quoted term".to_string(), 0)
+        );
+    }
+
+    #[test]
+    fn error_points_at_the_problem() {
+        let error = parse("nobody AND (frog OR bog").unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            format!(
+                "invalid query: expected ')', found end of query at column 24\n  \
+                 nobody AND (frog OR bog\n  {}^",
+                " ".repeat(23)
+            )
+        );
+    }
+}
@@ -0,0 +1,60 @@
+use std::fs::File;
+use std::io::{self, Read};
+use std::ops::Deref;
+
+use memmap2::Mmap;
+
+// Files at least this large are memory-mapped unless told otherwise; below it
+// the cost of setting up the mapping outweighs the copy it saves.
+pub const MMAP_THRESHOLD: u64 = 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Backend {
+    Auto,
+    Mmap,
+    Buffered,
+}
+
+// The bytes of a file, either copied onto the heap or mapped straight from
+// the page cache.
+pub enum Contents {
+    Buffered(Vec<u8>),
+    Mapped(Mmap),
+}
+
+impl Deref for Contents {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match self {
+            Contents::Buffered(bytes) => bytes,
+            Contents::Mapped(map) => map,
+        }
+    }
+}
+
+pub fn read(path: &str, backend: Backend) -> io::Result<Contents> {
+    let mut file = File::open(path)?;
+    let metadata = file.metadata()?;
+
+    // Pipes and other special files can't be mapped, and neither can an
+    // empty file.
+    let mappable = metadata.is_file() && metadata.len() > 0;
+    let mmap = match backend {
+        Backend::Auto => mappable && metadata.len() >= MMAP_THRESHOLD,
+        Backend::Mmap => mappable,
+        Backend::Buffered => false,
+    };
+
+    if mmap {
+        // SAFETY: the mapping is only ever read. If another process truncates
+        // the file while we search it, reads past the new end fault, which is
+        // the same trade-off grep and ripgrep make for the speed.
+        let map = unsafe { Mmap::map(&file)? };
+        return Ok(Contents::Mapped(map));
+    }
+
+    let mut bytes = Vec::with_capacity(metadata.len() as usize);
+    file.read_to_end(&mut bytes)?;
+    Ok(Contents::Buffered(bytes))
+}
@@ -0,0 +1,38 @@
+use regex::Regex;
+
+// How a file is cut up into the units that are matched and printed.
+#[derive(Clone)]
+pub enum Records {
+    Lines,
+    // Records end wherever the regex matches.
+    Separated(Regex),
+}
+
+impl Records {
+    // Blocks of text separated by one or more blank lines. Only lines that
+    // are entirely whitespace are consumed, so indentation is kept.
+    pub fn paragraphs() -> Records {
+        Records::Separated(Regex::new(r"\r?\n(?:[ \t]*\r?\n)+").unwrap())
+    }
+
+    pub fn separated_by(separator: &str) -> Result<Records, regex::Error> {
+        Ok(Records::Separated(Regex::new(separator)?))
+    }
+
+    // Splits `contents` into records. Empty records, such as the space before
+    // a leading separator, are skipped and don't count towards numbering.
+    pub fn split<'r, 'h: 'r>(
+        &'r self,
+        contents: &'h str,
+    ) -> Box<dyn Iterator<Item = &'h str> + 'r> {
+        match self {
+            Records::Lines => Box::new(contents.lines()),
+            Records::Separated(separator) => Box::new(
+                separator
+                    .split(contents)
+                    .map(|record| record.trim_end_matches(['\r', '\n']))
+                    .filter(|record| !record.is_empty()),
+            ),
+        }
+    }
+}
@@ -0,0 +1,315 @@
+use std::error::Error;
+use std::io::{self, Read};
+
+use crate::binary;
+use crate::matcher::Matcher;
+use crate::reader::{self, Backend};
+use crate::records::Records;
+use crate::tar;
+
+// A matching line, or record when searching by paragraph or separator, with
+// the byte ranges of every match inside it.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Match<'a> {
+    pub line_number: usize,
+    pub line: &'a str,
+    pub spans: Vec<(usize, usize)>,
+}
+
+impl<'a> Match<'a> {
+    pub fn matched_strings(&self) -> impl Iterator<Item = &'a str> + '_ {
+        self.spans
+            .iter()
+            .map(|&(start, end)| &self.line[start..end])
+    }
+}
+
+// Receives what a `Searcher` finds.
+pub trait Sink {
+    // Called before each file is searched, with its size in bytes.
+    fn begin(&mut self, _path: &str, _len: usize) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn matched(&mut self, path: &str, m: &Match) -> io::Result<()>;
+
+    // Called once for a binary file that matches, with the matched ranges of
+    // its bytes.
+    fn binary_matched(
+        &mut self,
+        path: &str,
+        bytes: &[u8],
+        spans: &[(usize, usize)],
+    ) -> io::Result<()>;
+
+    // Called when there is nothing left to search.
+    fn finish(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+// Throws away everything it's given, for when only whether something matched
+// is of interest.
+pub struct Discard;
+
+impl Sink for Discard {
+    fn matched(&mut self, _path: &str, _m: &Match) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn binary_matched(
+        &mut self,
+        _path: &str,
+        _bytes: &[u8],
+        _spans: &[(usize, usize)],
+    ) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+pub fn match_line<'a>(
+    matcher: &dyn Matcher,
+    line_number: usize,
+    line: &'a str,
+) -> Option<Match<'a>> {
+    matcher.find(line).map(|spans| Match {
+        line_number,
+        line,
+        spans,
+    })
+}
+
+pub fn search<'a>(matcher: &dyn Matcher, records: &Records, contents: &'a str) -> Vec<Match<'a>> {
+    records
+        .split(contents)
+        .enumerate()
+        .filter_map(|(i, record)| match_line(matcher, i + 1, record))
+        .collect()
+}
+
+// Runs a matcher over files, enforcing the per-file and overall limits on how
+// many matches are reported.
+pub struct Searcher {
+    pub matcher: Box<dyn Matcher>,
+    pub records: Records,
+    pub max_count: Option<usize>,
+    pub max_total: Option<usize>,
+    found: usize,
+}
+
+impl Searcher {
+    pub fn new(matcher: Box<dyn Matcher>) -> Searcher {
+        Searcher {
+            matcher,
+            records: Records::Lines,
+            max_count: None,
+            max_total: None,
+            found: 0,
+        }
+    }
+
+    // How many matches have been reported so far.
+    pub fn found(&self) -> usize {
+        self.found
+    }
+
+    // Whether `max_total` matches have been reported already.
+    pub fn is_done(&self) -> bool {
+        self.max_total.is_some_and(|max| self.found >= max)
+    }
+
+    fn limit(&self) -> usize {
+        let remaining = self.max_total.map_or(usize::MAX, |max| max - self.found);
+        self.max_count.unwrap_or(usize::MAX).min(remaining)
+    }
+
+    pub fn search_slice(
+        &mut self,
+        path: &str,
+        bytes: &[u8],
+        sink: &mut dyn Sink,
+    ) -> io::Result<()> {
+        if self.is_done() {
+            return Ok(());
+        }
+
+        let limit = self.limit();
+        sink.begin(path, bytes.len())?;
+
+        if binary::is_binary(bytes) {
+            // A matching binary file counts as a single match, however many
+            // spans it has; the query may match without any, as with NOT.
+            if let Some(mut spans) = self.matcher.find_bytes(bytes) {
+                if limit > 0 {
+                    spans.truncate(limit);
+                    self.found += 1;
+                    sink.binary_matched(path, bytes, &spans)?;
+                }
+            }
+            return Ok(());
+        }
+
+        // Text that isn't valid UTF-8 is searched with the bad sequences replaced.
+        let contents = String::from_utf8_lossy(bytes);
+        let matches = self
+            .records
+            .split(&contents)
+            .enumerate()
+            .filter_map(|(i, record)| match_line(self.matcher.as_ref(), i + 1, record))
+            .take(limit);
+
+        for m in matches {
+            self.found += 1;
+            sink.matched(path, &m)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn search_reader(
+        &mut self,
+        path: &str,
+        mut reader: impl Read,
+        sink: &mut dyn Sink,
+    ) -> io::Result<()> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes)?;
+        self.search_slice(path, &bytes, sink)
+    }
+
+    // Searches a file on disk. Entries of a tar archive are searched one by
+    // one, and reported as `archive.tar!inner/path`.
+    pub fn search_path(
+        &mut self,
+        path: &str,
+        backend: Backend,
+        sink: &mut dyn Sink,
+    ) -> Result<(), Box<dyn Error>> {
+        let bytes = reader::read(path, backend).map_err(|e| format!("{path}: {e}"))?;
+
+        if !tar::is_archive(path) {
+            return Ok(self.search_slice(path, &bytes, sink)?);
+        }
+
+        for entry in tar::entries(&bytes).map_err(|e| format!("{path}: {e}"))? {
+            self.search_slice(&format!("{path}!{}", entry.path), entry.data, sink)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::matcher::Literal;
+
+    // Collects everything it's given as `path:line:text` strings.
+    #[derive(Default)]
+    struct Collect(Vec<String>);
+
+    impl Sink for Collect {
+        fn matched(&mut self, path: &str, m: &Match) -> io::Result<()> {
+            self.0.push(format!("{path}:{}:{}", m.line_number, m.line));
+            Ok(())
+        }
+
+        fn binary_matched(
+            &mut self,
+            path: &str,
+            _bytes: &[u8],
+            spans: &[(usize, usize)],
+        ) -> io::Result<()> {
+            self.0.push(format!("{path}:binary:{spans:?}"));
+            Ok(())
+        }
+    }
+
+    const POEM: &str = "\
+I'm nobody! Who are you?
+Are you nobody, too?
+
+How dreary to be somebody!";
+
+    #[test]
+    fn reports_matching_lines() {
+        let mut searcher = Searcher::new(Box::new(Literal::new("body")));
+        let mut sink = Collect::default();
+        searcher
+            .search_slice("poem", POEM.as_bytes(), &mut sink)
+            .unwrap();
+
+        assert_eq!(
+            sink.0,
+            [
+                "poem:1:I'm nobody! Who are you?",
+                "poem:2:Are you nobody, too?",
+                "poem:4:How dreary to be somebody!",
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_matching_records() {
+        let mut searcher = Searcher::new(Box::new(Literal::new("too")));
+        searcher.records = Records::paragraphs();
+        let mut sink = Collect::default();
+        searcher
+            .search_slice("poem", POEM.as_bytes(), &mut sink)
+            .unwrap();
+
+        assert_eq!(
+            sink.0,
+            ["poem:1:I'm nobody! Who are you?\nAre you nobody, too?"]
+        );
+    }
+
+    #[test]
+    fn max_count_applies_per_file() {
+        let mut searcher = Searcher::new(Box::new(Literal::new("body")));
+        searcher.max_count = Some(1);
+        let mut sink = Collect::default();
+        searcher
+            .search_slice("a", POEM.as_bytes(), &mut sink)
+            .unwrap();
+        searcher
+            .search_slice("b", POEM.as_bytes(), &mut sink)
+            .unwrap();
+
+        assert_eq!(
+            sink.0,
+            [
+                "a:1:I'm nobody! Who are you?",
+                "b:1:I'm nobody! Who are you?"
+            ]
+        );
+    }
+
+    #[test]
+    fn max_total_applies_across_files() {
+        let mut searcher = Searcher::new(Box::new(Literal::new("body")));
+        searcher.max_total = Some(4);
+        let mut sink = Collect::default();
+        searcher
+            .search_slice("a", POEM.as_bytes(), &mut sink)
+            .unwrap();
+        searcher
+            .search_slice("b", POEM.as_bytes(), &mut sink)
+            .unwrap();
+
+        assert_eq!(sink.0.len(), 4);
+        assert_eq!(sink.0[3], "b:1:I'm nobody! Who are you?");
+        assert!(searcher.is_done());
+    }
+
+    #[test]
+    fn binary_files_are_reported_once() {
+        let mut searcher = Searcher::new(Box::new(Literal::new("frog")));
+        let mut sink = Collect::default();
+        searcher
+            .search_reader("bin", &b"frog\0frog"[..], &mut sink)
+            .unwrap();
+
+        assert_eq!(sink.0, ["bin:binary:[(0, 4), (5, 9)]"]);
+    }
+}
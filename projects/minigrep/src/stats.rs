@@ -0,0 +1,161 @@
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::io;
+use std::str::FromStr;
+use std::time::Duration;
+
+use crate::searcher::{Match, Sink};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatsFormat {
+    Human,
+    Json,
+}
+
+impl FromStr for StatsFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "human" => Ok(StatsFormat::Human),
+            "json" => Ok(StatsFormat::Json),
+            _ => Err(format!(
+                "unknown stats format '{s}' (expected human or json)"
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Stats {
+    pub files_scanned: usize,
+    pub bytes_read: u64,
+    pub lines_matched: usize,
+    pub elapsed: Duration,
+    pub frequencies: HashMap<String, usize>,
+}
+
+impl Stats {
+    pub fn record_file(&mut self, bytes_read: usize) {
+        self.files_scanned += 1;
+        self.bytes_read += bytes_read as u64;
+    }
+
+    pub fn record_match(&mut self, m: &Match) {
+        self.lines_matched += 1;
+
+        for text in m.matched_strings() {
+            self.record_text(text);
+        }
+    }
+
+    pub fn record_text(&mut self, text: &str) {
+        // Empty regex matches carry no information worth counting.
+        if !text.is_empty() {
+            *self.frequencies.entry(text.to_string()).or_insert(0) += 1;
+        }
+    }
+
+    // Distinct matched strings, most frequent first.
+    pub fn frequency_table(&self) -> Vec<(&str, usize)> {
+        let mut table: Vec<(&str, usize)> = self
+            .frequencies
+            .iter()
+            .map(|(text, &count)| (text.as_str(), count))
+            .collect();
+        table.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+        table
+    }
+
+    pub fn render(&self, format: StatsFormat) -> String {
+        match format {
+            StatsFormat::Human => self.render_human(),
+            StatsFormat::Json => self.render_json(),
+        }
+    }
+
+    fn elapsed_ms(&self) -> f64 {
+        self.elapsed.as_secs_f64() * 1000.0
+    }
+
+    fn render_human(&self) -> String {
+        let mut out = String::new();
+        writeln!(out, "files scanned: {}", self.files_scanned).unwrap();
+        writeln!(out, "bytes read:    {}", self.bytes_read).unwrap();
+        writeln!(out, "lines matched: {}", self.lines_matched).unwrap();
+        writeln!(out, "elapsed:       {:.3}ms", self.elapsed_ms()).unwrap();
+
+        let table = self.frequency_table();
+        if !table.is_empty() {
+            let width = table[0].1.to_string().len().max("count".len());
+            writeln!(out).unwrap();
+            writeln!(out, "{:>width$}  match", "count").unwrap();
+            for (text, count) in table {
+                writeln!(out, "{count:>width$}  {text}").unwrap();
+            }
+        }
+
+        out
+    }
+
+    fn render_json(&self) -> String {
+        let matches: Vec<String> = self
+            .frequency_table()
+            .into_iter()
+            .map(|(text, count)| format!("{{\"text\":{},\"count\":{count}}}", json_string(text)))
+            .collect();
+
+        format!(
+            "{{\"files_scanned\":{},\"bytes_read\":{},\"lines_matched\":{},\"elapsed_ms\":{:.3},\"matches\":[{}]}}\n",
+            self.files_scanned,
+            self.bytes_read,
+            self.lines_matched,
+            self.elapsed_ms(),
+            matches.join(",")
+        )
+    }
+}
+
+impl Sink for Stats {
+    fn begin(&mut self, _path: &str, len: usize) -> io::Result<()> {
+        self.record_file(len);
+        Ok(())
+    }
+
+    fn matched(&mut self, _path: &str, m: &Match) -> io::Result<()> {
+        self.record_match(m);
+        Ok(())
+    }
+
+    fn binary_matched(
+        &mut self,
+        _path: &str,
+        bytes: &[u8],
+        spans: &[(usize, usize)],
+    ) -> io::Result<()> {
+        // Like the searcher, a matching binary file counts as one match.
+        self.lines_matched += 1;
+        for &(start, end) in spans {
+            self.record_text(&String::from_utf8_lossy(&bytes[start..end]));
+        }
+        Ok(())
+    }
+}
+
+fn json_string(s: &str) -> String {
+    let mut quoted = String::with_capacity(s.len() + 2);
+    quoted.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c if (c as u32) < 0x20 => write!(quoted, "\\u{:04x}", c as u32).unwrap(),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
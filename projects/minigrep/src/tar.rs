@@ -0,0 +1,212 @@
+// A reader for uncompressed tar archives, enough to pull out the regular
+// files inside them. Handles the ustar layout along with the GNU long-name
+// and pax extended headers that tools use for paths over 100 bytes.
+
+const BLOCK: usize = 512;
+
+pub struct Entry<'a> {
+    pub path: String,
+    pub data: &'a [u8],
+}
+
+pub fn is_archive(path: &str) -> bool {
+    path.ends_with(".tar")
+}
+
+// Reads a numeric header field: octal text padded with NULs or spaces, or,
+// for values too large for that, big-endian binary flagged by the high bit.
+fn number(field: &[u8]) -> Result<u64, String> {
+    if field.first().is_some_and(|&b| b & 0x80 != 0) {
+        let value = field[1..]
+            .iter()
+            .fold(u64::from(field[0] & 0x7f), |n, &b| n << 8 | u64::from(b));
+        return Ok(value);
+    }
+
+    let text = String::from_utf8_lossy(field);
+    let text = text.trim_matches(|c: char| c == '\0' || c == ' ');
+    if text.is_empty() {
+        return Ok(0);
+    }
+    u64::from_str_radix(text, 8).map_err(|_| format!("invalid number {text:?} in tar header"))
+}
+
+// Reads a NUL-terminated text field.
+fn text(field: &[u8]) -> String {
+    let end = field.iter().position(|&b| b == 0).unwrap_or(field.len());
+    String::from_utf8_lossy(&field[..end]).into_owned()
+}
+
+fn checksum_matches(header: &[u8]) -> bool {
+    let Ok(expected) = number(&header[148..156]) else {
+        return false;
+    };
+    // The checksum is computed with its own field taken to be all spaces.
+    let actual: u64 = header
+        .iter()
+        .enumerate()
+        .map(|(i, &b)| {
+            if (148..156).contains(&i) {
+                u64::from(b' ')
+            } else {
+                u64::from(b)
+            }
+        })
+        .sum();
+    expected == actual
+}
+
+// The `path` record of a pax extended header, if it has one. Records look
+// like `"30 path=some/long/file/name\n"`, the number being the record length.
+fn pax_path(data: &[u8]) -> Option<String> {
+    let mut rest = data;
+
+    while !rest.is_empty() {
+        let space = rest.iter().position(|&b| b == b' ')?;
+        let len: usize = std::str::from_utf8(&rest[..space]).ok()?.parse().ok()?;
+        let record = rest.get(space + 1..len)?;
+        let record = record.strip_suffix(b"\n").unwrap_or(record);
+
+        if let Some(path) = record.strip_prefix(b"path=") {
+            return Some(String::from_utf8_lossy(path).into_owned());
+        }
+        rest = &rest[len..];
+    }
+
+    None
+}
+
+pub fn entries(archive: &[u8]) -> Result<Vec<Entry<'_>>, String> {
+    let mut entries = Vec::new();
+    let mut offset = 0;
+    let mut long_path = None;
+
+    while offset + BLOCK <= archive.len() {
+        let header = &archive[offset..offset + BLOCK];
+
+        // The archive ends with zero-filled blocks.
+        if header.iter().all(|&b| b == 0) {
+            break;
+        }
+
+        if !checksum_matches(header) {
+            return Err(format!("bad tar header checksum at offset {offset}"));
+        }
+
+        // A size too big to add up can't fit in the archive either.
+        let truncated = || format!("truncated tar entry at offset {offset}");
+        let size = usize::try_from(number(&header[124..136])?).map_err(|_| truncated())?;
+        let start = offset + BLOCK;
+        let end = start.checked_add(size).ok_or_else(truncated)?;
+        let data = archive.get(start..end).ok_or_else(truncated)?;
+        offset = size
+            .div_ceil(BLOCK)
+            .checked_mul(BLOCK)
+            .and_then(|padded| start.checked_add(padded))
+            .ok_or_else(truncated)?;
+
+        match header[156] {
+            // GNU long name: the data is the name of the next entry.
+            b'L' => long_path = Some(text(data)),
+            // pax extended header: may carry the path of the next entry.
+            b'x' => long_path = pax_path(data).or(long_path),
+            // Regular files; everything else (directories, links, devices) is skipped.
+            b'0' | b'\0' | b'7' => {
+                let path = long_path.take().unwrap_or_else(|| {
+                    let name = text(&header[0..100]);
+                    // Only POSIX ustar has a prefix there; GNU headers, with
+                    // magic "ustar  \0", keep access and change times in it.
+                    let prefix = if &header[257..263] == b"ustar\0" {
+                        text(&header[345..500])
+                    } else {
+                        String::new()
+                    };
+                    if prefix.is_empty() {
+                        name
+                    } else {
+                        format!("{prefix}/{name}")
+                    }
+                });
+                entries.push(Entry { path, data });
+            }
+            _ => long_path = None,
+        }
+    }
+
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A header for a regular file, with `size` as its raw size field.
+    fn header(path: &str, size: &[u8; 12]) -> Vec<u8> {
+        let mut header = vec![0; BLOCK];
+        header[..path.len()].copy_from_slice(path.as_bytes());
+        header[124..136].copy_from_slice(size);
+        header[156] = b'0';
+        seal(&mut header);
+        header
+    }
+
+    // Fills in the checksum, after the rest of the header is written.
+    fn seal(header: &mut [u8]) {
+        header[148..156].fill(b' ');
+        let checksum: u32 = header.iter().map(|&b| u32::from(b)).sum();
+        header[148..156].copy_from_slice(format!("{checksum:06o}\0 ").as_bytes());
+    }
+
+    #[test]
+    fn reads_regular_files() {
+        let mut archive = header("poem.txt", b"00000000005\0");
+        archive.extend(b"frogs");
+        archive.resize(BLOCK * 4, 0);
+
+        let entries = entries(&archive).unwrap();
+
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].path, "poem.txt");
+        assert_eq!(entries[0].data, b"frogs");
+    }
+
+    #[test]
+    fn gnu_headers_have_no_path_prefix() {
+        let mut archive = header("t2/f.txt", b"00000000005\0");
+        archive[257..265].copy_from_slice(b"ustar  \0");
+        // GNU keeps the access time where ustar has its prefix.
+        archive[345..357].copy_from_slice(b"15265327763\0");
+        seal(&mut archive);
+        archive.extend(b"frogs");
+        archive.resize(BLOCK * 4, 0);
+
+        let entries = entries(&archive).unwrap();
+
+        assert_eq!(entries[0].path, "t2/f.txt");
+    }
+
+    #[test]
+    fn ustar_prefixes_are_joined_to_the_name() {
+        let mut archive = header("f.txt", b"00000000005\0");
+        archive[257..263].copy_from_slice(b"ustar\0");
+        archive[345..347].copy_from_slice(b"t2");
+        seal(&mut archive);
+        archive.extend(b"frogs");
+        archive.resize(BLOCK * 4, 0);
+
+        let entries = entries(&archive).unwrap();
+
+        assert_eq!(entries[0].path, "t2/f.txt");
+    }
+
+    #[test]
+    fn huge_sizes_are_truncated_entries() {
+        let mut archive = header("huge", &[0xff; 12]);
+        archive.resize(BLOCK * 3, 0);
+
+        assert_eq!(
+            entries(&archive).err().unwrap(),
+            "truncated tar entry at offset 0"
+        );
+    }
+}
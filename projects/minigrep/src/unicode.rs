@@ -0,0 +1,38 @@
+// Positions as a reader sees them. Matchers report byte offsets, but a byte
+// offset can fall inside a multi-byte char, and even a char offset can fall
+// between an emoji and its modifier or a letter and its combining accent.
+// These helpers work in grapheme clusters, the units a terminal draws.
+
+use unicode_segmentation::UnicodeSegmentation;
+
+// The 1-based column of the grapheme containing byte `offset`, counted from
+// the start of the line it's on.
+pub fn column(text: &str, offset: usize) -> usize {
+    let line_start = text[..offset].rfind('\n').map_or(0, |i| i + 1);
+    text[line_start..offset].graphemes(true).count() + 1
+}
+
+// Widens each span to whole graphemes, joining spans that end up touching the
+// same grapheme, so highlighting never cuts one in half.
+pub fn snap_to_graphemes(text: &str, spans: &[(usize, usize)]) -> Vec<(usize, usize)> {
+    let boundaries: Vec<usize> = text
+        .grapheme_indices(true)
+        .map(|(i, _)| i)
+        .chain(Some(text.len()))
+        .collect();
+
+    let mut snapped: Vec<(usize, usize)> = Vec::with_capacity(spans.len());
+
+    for &(start, end) in spans {
+        // The last boundary at or before `start`, and the first at or after `end`.
+        let start = boundaries[boundaries.partition_point(|&b| b <= start) - 1];
+        let end = boundaries[boundaries.partition_point(|&b| b < end)];
+
+        match snapped.last_mut() {
+            Some(last) if start < last.1 => last.1 = last.1.max(end),
+            _ => snapped.push((start, end)),
+        }
+    }
+
+    snapped
+}
@@ -0,0 +1,118 @@
+use std::fs;
+use std::path::Path;
+
+use crate::filter::FileFilter;
+
+// Expands directories among `paths` into the files beneath them, in sorted
+// order. Hidden entries (names starting with a dot) are skipped and symbolic
+// links to directories aren't followed, so `.git` and link cycles are left
+// alone. Files inside directories must pass `filter`; paths that aren't
+// directories are passed through untouched.
+//
+// Entries that can't be read are left out, and a `path: error` message for
+// each is added to `skipped`, so one bad directory doesn't end the search.
+pub fn files(paths: &[String], filter: &FileFilter, skipped: &mut Vec<String>) -> Vec<String> {
+    let mut files = Vec::new();
+
+    for path in paths {
+        if Path::new(path).is_dir() {
+            walk(Path::new(path), filter, &mut files, skipped);
+        } else {
+            files.push(path.clone());
+        }
+    }
+
+    files
+}
+
+// The directories at or beneath `paths`, for watching. Those that can't be
+// read are skipped in the same way as by `files`.
+pub fn directories(paths: &[String], skipped: &mut Vec<String>) -> Vec<String> {
+    let mut directories = Vec::new();
+
+    for path in paths {
+        if Path::new(path).is_dir() {
+            directories.push(path.clone());
+            collect_directories(Path::new(path), &mut directories, skipped);
+        }
+    }
+
+    directories
+}
+
+fn entries(dir: &Path, skipped: &mut Vec<String>) -> Vec<(fs::DirEntry, fs::FileType)> {
+    let read = match fs::read_dir(dir) {
+        Ok(read) => read,
+        Err(e) => {
+            skipped.push(format!("{}: {e}", dir.display()));
+            return Vec::new();
+        }
+    };
+
+    let mut entries = Vec::new();
+    for entry in read.filter_map(Result::ok) {
+        if entry.file_name().to_string_lossy().starts_with('.') {
+            continue;
+        }
+        match entry.file_type() {
+            Ok(file_type) => entries.push((entry, file_type)),
+            Err(e) => skipped.push(format!("{}: {e}", entry.path().display())),
+        }
+    }
+    entries.sort_by_key(|(entry, _)| entry.file_name());
+    entries
+}
+
+fn walk(dir: &Path, filter: &FileFilter, files: &mut Vec<String>, skipped: &mut Vec<String>) {
+    for (entry, file_type) in entries(dir, skipped) {
+        let path = entry.path();
+
+        if file_type.is_dir() {
+            walk(&path, filter, files, skipped);
+            continue;
+        }
+
+        // Follows symbolic links, so a link to a file is searched like the file.
+        let Ok(metadata) = path.metadata() else {
+            continue;
+        };
+        if metadata.is_file() && filter.accepts(&path, &metadata) {
+            files.push(path.to_string_lossy().into_owned());
+        }
+    }
+}
+
+fn collect_directories(dir: &Path, directories: &mut Vec<String>, skipped: &mut Vec<String>) {
+    for (entry, file_type) in entries(dir, skipped) {
+        if file_type.is_dir() {
+            let path = entry.path();
+            directories.push(path.to_string_lossy().into_owned());
+            collect_directories(&path, directories, skipped);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unreadable_directories_are_skipped_with_their_path() {
+        let dir = std::env::temp_dir().join(format!("minigrep-walk-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("a.txt"), "frog\n").unwrap();
+        let gone = dir.join("gone");
+
+        let (mut files, mut skipped) = (Vec::new(), Vec::new());
+        walk(&gone, &FileFilter::default(), &mut files, &mut skipped);
+        walk(&dir, &FileFilter::default(), &mut files, &mut skipped);
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(files, [dir.join("a.txt").to_string_lossy()]);
+        assert_eq!(skipped.len(), 1);
+        assert!(
+            skipped[0].starts_with(&format!("{}: ", gone.display())),
+            "{skipped:?}"
+        );
+    }
+}
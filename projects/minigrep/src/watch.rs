@@ -0,0 +1,187 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+use crate::filter::FileFilter;
+use crate::walk;
+
+pub const CLEAR_SCREEN: &str = "\u{001b}[2J\u{001b}[H";
+
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+// Editors often save in several steps (write a temporary file, rename it,
+// touch it); waiting a moment after the first event lets them finish.
+const SETTLE: Duration = Duration::from_millis(100);
+
+// The modification time and size of every file being watched. A file that
+// can't be read is recorded as `None`, so it appearing later counts as a change.
+type Snapshot = HashMap<String, Option<(SystemTime, u64)>>;
+
+fn snapshot(paths: &[String]) -> Snapshot {
+    // Anything unreadable was reported by the search itself.
+    let mut skipped = Vec::new();
+    let mut files = walk::files(paths, &FileFilter::default(), &mut skipped);
+    files.extend(paths.iter().cloned());
+    files.extend(walk::directories(paths, &mut skipped));
+
+    files
+        .into_iter()
+        .map(|path| {
+            let stamp = fs::metadata(&path)
+                .ok()
+                .and_then(|metadata| Some((metadata.modified().ok()?, metadata.len())));
+            (path, stamp)
+        })
+        .collect()
+}
+
+// Notices changes to files, or to the contents of directories, from the
+// moment it is created.
+pub enum Watcher {
+    #[cfg(target_os = "linux")]
+    Inotify(fs::File),
+    Poll {
+        paths: Vec<String>,
+        before: Snapshot,
+    },
+}
+
+impl Watcher {
+    // Uses inotify where it's available, and falls back to polling file
+    // modification times otherwise.
+    pub fn new(paths: &[String]) -> Watcher {
+        #[cfg(target_os = "linux")]
+        if let Some(inotify) = inotify::watch(paths) {
+            return Watcher::Inotify(inotify);
+        }
+
+        Watcher::Poll {
+            paths: paths.to_vec(),
+            before: snapshot(paths),
+        }
+    }
+
+    // Blocks until something changed.
+    pub fn wait(&mut self) -> io::Result<()> {
+        match self {
+            #[cfg(target_os = "linux")]
+            Watcher::Inotify(inotify) => {
+                let mut events = [0u8; 4096];
+                io::Read::read(inotify, &mut events)?;
+            }
+            Watcher::Poll { paths, before } => loop {
+                thread::sleep(POLL_INTERVAL);
+                if snapshot(paths) != *before {
+                    break;
+                }
+            },
+        }
+
+        thread::sleep(SETTLE);
+        Ok(())
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod inotify {
+    use std::ffi::CString;
+    use std::fs::File;
+    use std::os::fd::{FromRawFd, OwnedFd};
+    use std::os::unix::ffi::OsStrExt;
+    use std::path::Path;
+
+    use crate::walk;
+
+    const EVENTS: u32 = libc::IN_MODIFY
+        | libc::IN_CLOSE_WRITE
+        | libc::IN_ATTRIB
+        | libc::IN_CREATE
+        | libc::IN_DELETE
+        | libc::IN_DELETE_SELF
+        | libc::IN_MOVE_SELF
+        | libc::IN_MOVED_FROM
+        | libc::IN_MOVED_TO;
+
+    // An inotify instance watching `paths` and every directory beneath them,
+    // as inotify itself doesn't recurse. Returns `None` if inotify can't be
+    // used, e.g. when the per-user watch limit has been reached.
+    pub fn watch(paths: &[String]) -> Option<File> {
+        // SAFETY: inotify_init1 takes no pointers; a non-negative result is a
+        // new descriptor that nothing else owns.
+        let fd = unsafe { libc::inotify_init1(libc::IN_CLOEXEC) };
+        if fd < 0 {
+            return None;
+        }
+        let inotify = File::from(unsafe { OwnedFd::from_raw_fd(fd) });
+
+        // A directory that can't be read can't be watched either.
+        let mut skipped = Vec::new();
+        let mut targets = walk::directories(paths, &mut skipped);
+        if !skipped.is_empty() {
+            return None;
+        }
+        for path in paths {
+            let path = Path::new(path);
+            if path.is_dir() {
+                continue;
+            }
+            // A file that doesn't exist yet is noticed through its directory.
+            if path.exists() {
+                targets.push(path.to_string_lossy().into_owned());
+            } else {
+                let parent = path.parent().filter(|p| !p.as_os_str().is_empty());
+                let parent = parent.unwrap_or(Path::new("."));
+                targets.push(parent.to_string_lossy().into_owned());
+            }
+        }
+
+        for target in targets {
+            let target = CString::new(Path::new(&target).as_os_str().as_bytes()).ok()?;
+            // SAFETY: `fd` is open for as long as `inotify` lives, and `target`
+            // is a NUL-terminated string that outlives the call.
+            if unsafe { libc::inotify_add_watch(fd, target.as_ptr(), EVENTS) } < 0 {
+                return None;
+            }
+        }
+
+        Some(inotify)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc;
+
+    #[test]
+    fn polling_notices_a_changed_file() {
+        let path = std::env::temp_dir().join(format!("minigrep-watch-{}", std::process::id()));
+        fs::write(&path, "frog\n").unwrap();
+        let paths = vec![path.to_string_lossy().into_owned()];
+        let mut watcher = Watcher::Poll {
+            before: snapshot(&paths),
+            paths,
+        };
+
+        let writer = {
+            let path = path.clone();
+            thread::spawn(move || {
+                thread::sleep(Duration::from_millis(50));
+                fs::write(path, "frogs and toads\n").unwrap();
+            })
+        };
+        let (done, waited) = mpsc::channel();
+        thread::spawn(move || done.send(watcher.wait().is_ok()));
+
+        let result = waited.recv_timeout(Duration::from_secs(10));
+        writer.join().unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(
+            result,
+            Ok(true),
+            "wait() should return once the file changes"
+        );
+    }
+}
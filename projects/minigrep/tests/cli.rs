@@ -0,0 +1,730 @@
+use std::path::PathBuf;
+use std::process::{Command, Output};
+
+fn fixture(name: &str) -> String {
+    let path: PathBuf = [env!("CARGO_MANIFEST_DIR"), "tests", "fixtures", name]
+        .iter()
+        .collect();
+    path.to_string_lossy().into_owned()
+}
+
+fn minigrep(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_minigrep"))
+        .args(args)
+        .env_remove("IGNORE_CASE")
+        .output()
+        .expect("failed to run minigrep")
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8(output.stdout.clone()).unwrap()
+}
+
+fn stderr(output: &Output) -> String {
+    String::from_utf8(output.stderr.clone()).unwrap()
+}
+
+#[test]
+fn prints_matching_lines_with_line_numbers() {
+    let output = minigrep(&["nobody", &fixture("poem.txt")]);
+
+    assert!(output.status.success());
+    assert_eq!(
+        stdout(&output),
+        "1:I'm nobody! Who are you?\n2:Are you nobody, too?\n"
+    );
+    assert_eq!(stderr(&output), "");
+}
+
+#[test]
+fn search_is_case_sensitive_by_default() {
+    let output = minigrep(&["how", &fixture("poem.txt")]);
+
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(stdout(&output), "");
+}
+
+#[test]
+fn ignore_case_flag() {
+    let output = minigrep(&["-i", "how", &fixture("poem.txt")]);
+
+    assert!(output.status.success());
+    assert_eq!(
+        stdout(&output),
+        "6:How dreary to be somebody!\n7:How public, like a frog\n"
+    );
+}
+
+#[test]
+fn ignore_case_environment_variable() {
+    let output = Command::new(env!("CARGO_BIN_EXE_minigrep"))
+        .args(["frog", &fixture("frogs.txt")])
+        .env("IGNORE_CASE", "1")
+        .output()
+        .unwrap();
+
+    assert!(output.status.success());
+    assert_eq!(
+        stdout(&output),
+        "1:A frog sat on a log.\n3:Frogs croak; logs do not.\n"
+    );
+}
+
+#[test]
+fn query_is_literal_without_regex_flag() {
+    let output = minigrep(&["a.", &fixture("frogs.txt")]);
+
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(stdout(&output), "");
+}
+
+#[test]
+fn regex_flag() {
+    let output = minigrep(&["-E", r"\b[bl]og\b", &fixture("frogs.txt")]);
+
+    assert!(output.status.success());
+    assert_eq!(
+        stdout(&output),
+        "1:A frog sat on a log.\n2:The log was in a bog.\n"
+    );
+}
+
+#[test]
+fn multiple_files_are_prefixed_with_their_path() {
+    let poem = fixture("poem.txt");
+    let frogs = fixture("frogs.txt");
+    let output = minigrep(&["frog", &poem, &frogs]);
+
+    assert!(output.status.success());
+    assert_eq!(
+        stdout(&output),
+        format!("{poem}:7:How public, like a frog\n{frogs}:1:A frog sat on a log.\n")
+    );
+}
+
+#[test]
+fn stats_report() {
+    let output = minigrep(&["--stats", "-E", "[bl]og", &fixture("frogs.txt")]);
+    let report = stdout(&output);
+
+    assert!(output.status.success());
+    assert!(report.contains("files scanned: 1\n"));
+    assert!(report.contains("bytes read:    69\n"));
+    assert!(report.contains("lines matched: 3\n"));
+    assert!(report.ends_with("count  match\n    3  log\n    1  bog\n"));
+}
+
+#[test]
+fn stats_report_as_json() {
+    let output = minigrep(&["--stats=json", "log", &fixture("frogs.txt")]);
+    let report = stdout(&output);
+
+    assert!(output.status.success());
+    assert!(report.starts_with("{\"files_scanned\":1,\"bytes_read\":69,\"lines_matched\":3,"));
+    assert!(report.ends_with(",\"matches\":[{\"text\":\"log\",\"count\":3}]}\n"));
+}
+
+#[test]
+fn stats_count_a_matching_binary_file() {
+    let binary = fixture("binary.bin");
+    let human = stdout(&minigrep(&["--stats", "nobody", &binary]));
+    let json = stdout(&minigrep(&["--stats=json", "nobody", &binary]));
+
+    assert!(human.contains("lines matched: 1\n"));
+    assert!(human.ends_with("count  match\n    2  nobody\n"));
+    assert!(json.contains("\"lines_matched\":1,"));
+    assert!(json.ends_with(",\"matches\":[{\"text\":\"nobody\",\"count\":2}]}\n"));
+}
+
+#[test]
+fn missing_query() {
+    let output = minigrep(&[]);
+
+    assert_eq!(output.status.code(), Some(2));
+    assert_eq!(stdout(&output), "");
+    assert_eq!(
+        stderr(&output),
+        "Problem parsing arguments: Didn't get a query string\n"
+    );
+}
+
+#[test]
+fn missing_file_name() {
+    let output = minigrep(&["nobody"]);
+
+    assert_eq!(output.status.code(), Some(2));
+    assert_eq!(stdout(&output), "");
+    assert_eq!(
+        stderr(&output),
+        "Problem parsing arguments: Didn't get a file name\n"
+    );
+}
+
+#[test]
+fn unknown_option() {
+    let output = minigrep(&["--frobnicate", "nobody", &fixture("poem.txt")]);
+
+    assert_eq!(output.status.code(), Some(2));
+    assert_eq!(
+        stderr(&output),
+        "Problem parsing arguments: unknown option '--frobnicate'\n"
+    );
+}
+
+#[test]
+fn missing_file() {
+    let missing = fixture("does-not-exist.txt");
+    let output = minigrep(&["nobody", &missing]);
+
+    assert_eq!(output.status.code(), Some(2));
+    assert_eq!(stdout(&output), "");
+    assert!(stderr(&output).starts_with(&format!("Application error: {missing}: ")));
+}
+
+#[test]
+fn invalid_regex() {
+    let output = minigrep(&["-E", "(unclosed", &fixture("poem.txt")]);
+
+    assert_eq!(output.status.code(), Some(2));
+    assert!(stderr(&output).starts_with("Application error: regex parse error"));
+}
+
+#[test]
+fn help_lists_every_option() {
+    let output = minigrep(&["--help"]);
+    let help = stdout(&output);
+
+    assert!(output.status.success());
+    assert!(help.contains("Usage: minigrep [OPTIONS] QUERY PATH..."));
+    for option in [
+        "--ignore-case",
+        "--regex",
+        "--stats[=FORMAT]",
+        "--generate KIND",
+    ] {
+        assert!(help.contains(option), "help is missing {option}");
+    }
+}
+
+#[test]
+fn generate_man_page() {
+    let output = minigrep(&["--generate", "man"]);
+    let man = stdout(&output);
+
+    assert!(output.status.success());
+    assert!(man.starts_with(".TH MINIGREP 1 "));
+    assert!(man.contains("\\fB\\-i\\fR, \\fB\\-\\-ignore\\-case\\fR\n"));
+    assert!(man.contains("\\fB\\-\\-stats\\fR[=\\fIFORMAT\\fR]\n"));
+}
+
+#[test]
+fn generate_completions() {
+    for (shell, expected) in [
+        ("bash", "complete -o filenames -F _minigrep minigrep\n"),
+        ("zsh", "#compdef minigrep\n"),
+        ("fish", "complete -c minigrep -s i -l ignore-case "),
+    ] {
+        let output = minigrep(&["--generate", "completions", shell]);
+
+        assert!(output.status.success());
+        assert!(stdout(&output).contains(expected), "{shell} completions");
+        assert!(stdout(&output).contains("ignore-case"));
+    }
+}
+
+#[test]
+fn generate_completions_for_unknown_shell() {
+    let output = minigrep(&["--generate", "completions", "tcsh"]);
+
+    assert_eq!(output.status.code(), Some(2));
+    assert_eq!(
+        stderr(&output),
+        "Problem parsing arguments: unknown shell 'tcsh' (expected bash, zsh, fish)\n"
+    );
+}
+
+#[test]
+fn binary_file_matches() {
+    let binary = fixture("binary.bin");
+    let output = minigrep(&["nobody", &binary]);
+
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), format!("Binary file {binary} matches\n"));
+}
+
+#[test]
+fn binary_file_without_match_prints_nothing() {
+    let output = minigrep(&["frog", &fixture("binary.bin")]);
+
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(stdout(&output), "");
+}
+
+#[test]
+fn binary_file_with_zero_max_count_prints_nothing() {
+    let output = minigrep(&["-m", "0", "nobody", &fixture("binary.bin")]);
+
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(stdout(&output), "");
+}
+
+#[test]
+fn binary_file_matching_empty_pattern() {
+    let binary = fixture("binary.bin");
+    let output = minigrep(&["", &binary]);
+
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), format!("Binary file {binary} matches\n"));
+}
+
+#[test]
+fn binary_file_matching_negated_query() {
+    let binary = fixture("binary.bin");
+    let output = minigrep(&["--bool", "NOT zzz", &binary]);
+
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), format!("Binary file {binary} matches\n"));
+}
+
+#[test]
+fn binary_hexdump() {
+    let binary = fixture("binary.bin");
+    let output = minigrep(&["--binary-hexdump", "end", &binary]);
+
+    assert!(output.status.success());
+    assert_eq!(
+        stdout(&output),
+        format!(
+            "{binary}: match at offset 0x0000002b\n\
+             00000010  79 20 68 65 72 65 00 6d  6f 72 65 20 62 79 74 65  |y here.more byte|\n\
+             00000020  73 20 6e 6f 62 6f 64 79  ff fe 20 65 6e 64        |s nobody.. end|\n"
+        )
+    );
+}
+
+#[test]
+fn mmap_and_buffered_readers_agree() {
+    let poem = fixture("poem.txt");
+    let mapped = minigrep(&["--mmap", "nobody", &poem]);
+    let buffered = minigrep(&["--no-mmap", "nobody", &poem]);
+
+    assert!(mapped.status.success());
+    assert!(buffered.status.success());
+    assert_eq!(stdout(&mapped), stdout(&buffered));
+    assert_eq!(
+        stdout(&mapped),
+        "1:I'm nobody! Who are you?\n2:Are you nobody, too?\n"
+    );
+}
+
+#[test]
+fn compare_reports_added_and_removed_matches() {
+    let output = minigrep(&[
+        "--compare",
+        &fixture("poem.txt"),
+        &fixture("poem-v2.txt"),
+        "body",
+    ]);
+
+    assert!(output.status.success());
+    assert_eq!(
+        stdout(&output),
+        "-2:Are you nobody, too?\n+2:Are you somebody, too?\n+4:A new nobody line\n"
+    );
+}
+
+#[test]
+fn compare_ignores_unchanged_lines() {
+    let output = minigrep(&[
+        "--compare",
+        &fixture("poem.txt"),
+        &fixture("poem-v2.txt"),
+        "frog",
+    ]);
+
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(stdout(&output), "");
+}
+
+#[test]
+fn compare_needs_three_arguments() {
+    let output = minigrep(&["--compare", &fixture("poem.txt"), "body"]);
+
+    assert_eq!(output.status.code(), Some(2));
+    assert_eq!(
+        stderr(&output),
+        "Problem parsing arguments: --compare needs OLD NEW QUERY\n"
+    );
+}
+
+#[test]
+fn max_count_limits_each_file() {
+    let poem = fixture("poem.txt");
+    let frogs = fixture("frogs.txt");
+    let output = minigrep(&["-m", "1", "-i", "o", &poem, &frogs]);
+
+    assert!(output.status.success());
+    assert_eq!(
+        stdout(&output),
+        format!("{poem}:1:I'm nobody! Who are you?\n{frogs}:1:A frog sat on a log.\n")
+    );
+}
+
+#[test]
+fn max_total_limits_all_files() {
+    let frogs = fixture("frogs.txt");
+    let output = minigrep(&["--max-total", "2", "o", &frogs, &fixture("poem.txt")]);
+
+    assert!(output.status.success());
+    assert_eq!(
+        stdout(&output),
+        format!("{frogs}:1:A frog sat on a log.\n{frogs}:2:The log was in a bog.\n")
+    );
+}
+
+#[test]
+fn sort_by_line() {
+    let poem = fixture("poem.txt");
+    let frogs = fixture("frogs.txt");
+    let output = minigrep(&["--sort=line", "-E", "o[gb]", &poem, &frogs]);
+
+    assert!(output.status.success());
+    assert_eq!(
+        stdout(&output),
+        format!(
+            "{poem}:1:I'm nobody! Who are you?\n\
+             {frogs}:1:A frog sat on a log.\n\
+             {poem}:2:Are you nobody, too?\n\
+             {frogs}:2:The log was in a bog.\n\
+             {frogs}:3:Frogs croak; logs do not.\n\
+             {poem}:7:How public, like a frog\n\
+             {poem}:9:To an admiring bog!\n"
+        )
+    );
+}
+
+#[test]
+fn sort_by_path() {
+    let poem = fixture("poem.txt");
+    let frogs = fixture("frogs.txt");
+    let output = minigrep(&["--sort", "path", "frog", &poem, &frogs]);
+
+    assert!(output.status.success());
+    assert_eq!(
+        stdout(&output),
+        format!("{frogs}:1:A frog sat on a log.\n{poem}:7:How public, like a frog\n")
+    );
+}
+
+#[test]
+fn unique_collapses_identical_lines() {
+    let output = minigrep(&["--unique", "error", &fixture("log.txt")]);
+
+    assert!(output.status.success());
+    assert_eq!(
+        stdout(&output),
+        "      3 error: disk full\n      1 error: network down\n"
+    );
+}
+
+#[test]
+fn invalid_max_count() {
+    let output = minigrep(&["--max-count", "lots", "error", &fixture("log.txt")]);
+
+    assert_eq!(output.status.code(), Some(2));
+    assert_eq!(
+        stderr(&output),
+        "Problem parsing arguments: invalid number 'lots' for '--max-count'\n"
+    );
+}
+
+#[test]
+fn boolean_query() {
+    let output = minigrep(&[
+        "--bool",
+        "you AND NOT (nobody OR know)",
+        &fixture("poem.txt"),
+    ]);
+
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "8:To tell your name the livelong day\n");
+
+    let output = minigrep(&[
+        "--bool",
+        "-i",
+        "how AND NOT frog OR bog",
+        &fixture("poem.txt"),
+    ]);
+
+    assert!(output.status.success());
+    assert_eq!(
+        stdout(&output),
+        "6:How dreary to be somebody!\n9:To an admiring bog!\n"
+    );
+}
+
+#[test]
+fn boolean_query_syntax_error() {
+    let output = minigrep(&["--bool", "frog AND", &fixture("poem.txt")]);
+
+    assert_eq!(output.status.code(), Some(2));
+    assert_eq!(
+        stderr(&output),
+        "Application error: invalid query: expected a term, found end of query at column 9\n  \
+         frog AND\n          ^\n"
+    );
+}
+
+#[test]
+fn paragraph_mode_prints_whole_paragraphs() {
+    let output = minigrep(&["--paragraph", "frog", &fixture("poem.txt")]);
+
+    assert!(output.status.success());
+    assert_eq!(
+        stdout(&output),
+        "2:How dreary to be somebody!\n\
+         How public, like a frog\n\
+         To tell your name the livelong day\n\
+         To an admiring bog!\n"
+    );
+}
+
+#[test]
+fn paragraph_mode_keeps_indentation() {
+    let path = std::env::temp_dir().join(format!("minigrep-indented-{}.txt", std::process::id()));
+    std::fs::write(&path, "a\n  \n\n    indented frog\n    and toad\n").unwrap();
+
+    let output = minigrep(&["--paragraph", "frog", path.to_str().unwrap()]);
+    std::fs::remove_file(&path).unwrap();
+
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "2:    indented frog\n    and toad\n");
+}
+
+#[test]
+fn boolean_query_per_paragraph() {
+    let output = minigrep(&["-p", "--bool", "nobody AND tell", &fixture("poem.txt")]);
+
+    assert!(output.status.success());
+    assert!(stdout(&output).starts_with("1:I'm nobody! Who are you?\n"));
+}
+
+#[test]
+fn record_separator() {
+    let output = minigrep(&[
+        "--record-separator",
+        r"[.;]\s*",
+        "log",
+        &fixture("frogs.txt"),
+    ]);
+
+    assert!(output.status.success());
+    assert_eq!(
+        stdout(&output),
+        "1:A frog sat on a log\n2:The log was in a bog\n4:logs do not\n"
+    );
+}
+
+#[test]
+fn invalid_record_separator() {
+    let output = minigrep(&["--record-separator", "(", "log", &fixture("frogs.txt")]);
+
+    assert_eq!(output.status.code(), Some(2));
+    assert!(stderr(&output).starts_with("Problem parsing arguments: invalid record separator: "));
+}
+
+#[test]
+fn searches_inside_tar_archives() {
+    let archive = fixture("release.tar");
+    let output = minigrep(&["-i", "frog", &archive]);
+
+    assert!(output.status.success());
+    assert_eq!(
+        stdout(&output),
+        format!(
+            "{archive}!release/notes/frogs.txt:1:A frog sat on a log.\n\
+             {archive}!release/notes/frogs.txt:3:Frogs croak; logs do not.\n\
+             {archive}!release/poem.txt:7:How public, like a frog\n"
+        )
+    );
+}
+
+#[test]
+fn corrupt_tar_archive() {
+    let archive = fixture("binary.bin");
+    let copy = std::env::temp_dir().join(format!("minigrep-corrupt-{}.tar", std::process::id()));
+    let mut bytes = std::fs::read(&archive).unwrap();
+    bytes.resize(1024, b'x');
+    std::fs::write(&copy, bytes).unwrap();
+
+    let output = minigrep(&["frog", copy.to_str().unwrap()]);
+    std::fs::remove_file(&copy).unwrap();
+
+    assert_eq!(output.status.code(), Some(2));
+    assert!(stderr(&output).contains("bad tar header checksum at offset 0"));
+}
+
+#[test]
+fn fuzzy_matching() {
+    let output = minigrep(&["--fuzzy", "1", "dreery", &fixture("poem.txt")]);
+
+    assert!(output.status.success());
+    assert_eq!(stdout(&output), "6:How dreary to be somebody!\n");
+}
+
+#[test]
+fn column_counts_graphemes_not_bytes() {
+    let output = minigrep(&["--column", "-E", "_cat|\"", &fixture("unicode.txt")]);
+
+    assert!(output.status.success());
+    assert_eq!(
+        stdout(&output),
+        "1:15:let heart_eyed_cat = '😻';\n2:12:let cafe\u{301} = \"café\"; // cat\n"
+    );
+}
+
+#[test]
+fn searches_directories_recursively() {
+    let dir = std::env::temp_dir().join(format!("minigrep-dir-{}", std::process::id()));
+    std::fs::create_dir_all(dir.join("sub")).unwrap();
+    std::fs::create_dir_all(dir.join(".hidden")).unwrap();
+    std::fs::write(dir.join("b.txt"), "a frog\n").unwrap();
+    std::fs::write(dir.join("sub/a.txt"), "no\nfrog two\n").unwrap();
+    std::fs::write(dir.join(".hidden/c.txt"), "frog\n").unwrap();
+
+    let output = minigrep(&["frog", dir.to_str().unwrap()]);
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    let dir = dir.display();
+    assert!(output.status.success());
+    assert_eq!(
+        stdout(&output),
+        format!("{dir}/b.txt:1:a frog\n{dir}/sub/a.txt:2:frog two\n")
+    );
+}
+
+#[test]
+fn filters_files_found_in_directories() {
+    let dir = std::env::temp_dir().join(format!("minigrep-filter-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("main.rs"), "// frog\n").unwrap();
+    std::fs::write(dir.join("notes.txt"), "frog\n").unwrap();
+    std::fs::write(dir.join("big.log"), format!("frog\n{}\n", "x".repeat(2048))).unwrap();
+    let old = std::fs::File::options()
+        .write(true)
+        .open(dir.join("notes.txt"))
+        .unwrap();
+    old.set_modified(std::time::SystemTime::now() - std::time::Duration::from_secs(3 * 86400))
+        .unwrap();
+    let path = dir.to_str().unwrap();
+
+    let by_type = minigrep(&["--type", "rust", "--type", "text", "frog", path]);
+    let by_size = minigrep(&["--max-filesize", "1K", "frog", path]);
+    let by_age = minigrep(&["--newer-than", "2d", "frog", path]);
+    let explicit = minigrep(&["--type", "rust", "frog", &format!("{path}/notes.txt")]);
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    let dir = dir.display();
+    assert_eq!(
+        stdout(&by_type),
+        format!("{dir}/main.rs:1:// frog\n{dir}/notes.txt:1:frog\n")
+    );
+    assert_eq!(
+        stdout(&by_size),
+        format!("{dir}/main.rs:1:// frog\n{dir}/notes.txt:1:frog\n")
+    );
+    assert_eq!(
+        stdout(&by_age),
+        format!("{dir}/big.log:1:frog\n{dir}/main.rs:1:// frog\n")
+    );
+    // Files named on the command line are searched whatever their type.
+    assert_eq!(stdout(&explicit), "1:frog\n");
+}
+
+#[test]
+fn file_types_from_config() {
+    let config = std::env::temp_dir().join(format!("minigrep-types-{}", std::process::id()));
+    std::fs::create_dir_all(config.join("minigrep")).unwrap();
+    std::fs::write(config.join("minigrep/types"), "# extra types\npoems: txt\n").unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_minigrep"))
+        .args(["--type", "poems", "nobody", &fixture("")])
+        .env("XDG_CONFIG_HOME", &config)
+        .output()
+        .unwrap();
+    let unknown = Command::new(env!("CARGO_BIN_EXE_minigrep"))
+        .args(["--type", "haiku", "nobody", &fixture("")])
+        .env("XDG_CONFIG_HOME", &config)
+        .output()
+        .unwrap();
+    std::fs::remove_dir_all(&config).unwrap();
+
+    assert!(output.status.success());
+    assert!(stdout(&output).contains("poem.txt:1:I'm nobody! Who are you?\n"));
+    assert!(!stdout(&output).contains(".log"));
+    assert_eq!(unknown.status.code(), Some(2));
+    assert!(
+        stderr(&unknown).contains("unknown file type 'haiku' (expected log, poems, rust, text)")
+    );
+}
+
+#[test]
+fn invalid_max_filesize() {
+    let output = minigrep(&["--max-filesize", "ten", "frog", &fixture("frogs.txt")]);
+
+    assert_eq!(output.status.code(), Some(2));
+    assert!(stderr(&output).contains("invalid size 'ten'"));
+}
+
+#[test]
+fn missing_file_does_not_stop_the_search() {
+    let poem = fixture("poem.txt");
+    let missing = fixture("does-not-exist.txt");
+    let frogs = fixture("frogs.txt");
+    let output = minigrep(&["frog", &poem, &missing, &frogs]);
+
+    assert_eq!(output.status.code(), Some(2));
+    assert!(stdout(&output).starts_with(&format!("{poem}:7:How public, like a frog\n")));
+    assert!(stdout(&output).contains(&format!("{frogs}:1:")));
+    assert!(stderr(&output).starts_with(&format!("Application error: {missing}: ")));
+}
+
+#[test]
+fn quiet_ignores_errors_once_something_matched() {
+    let output = minigrep(&[
+        "-q",
+        "frog",
+        &fixture("does-not-exist.txt"),
+        &fixture("frogs.txt"),
+    ]);
+
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout(&output), "");
+}
+
+#[test]
+fn quiet_prints_nothing() {
+    let found = minigrep(&["-q", "nobody", &fixture("poem.txt")]);
+    let not_found = minigrep(&["--quiet", "dragon", &fixture("poem.txt")]);
+    let error = minigrep(&["-q", "nobody", &fixture("missing.txt")]);
+
+    assert_eq!(found.status.code(), Some(0));
+    assert_eq!(stdout(&found), "");
+    assert_eq!(not_found.status.code(), Some(1));
+    assert_eq!(stdout(&not_found), "");
+    assert_eq!(error.status.code(), Some(2));
+}
+
+#[test]
+fn quiet_stops_at_the_first_match() {
+    // The missing file is never reached.
+    let output = minigrep(&[
+        "-q",
+        "nobody",
+        &fixture("poem.txt"),
+        &fixture("missing.txt"),
+    ]);
+
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stderr(&output), "");
+}